@@ -3,7 +3,7 @@ use lipsum::lipsum_with_rng;
 use rand_core::SeedableRng;
 use rand_pcg::Pcg64Mcg;
 
-use pig_latin::translate;
+use pig_latin::{translate, Translator};
 
 fn lorem_ipsum(bencher: &mut Criterion) {
     for seed in [0u64, 1, 2, 42, 69, 123456789] {
@@ -25,5 +25,34 @@ fn lorem_ipsum(bencher: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, lorem_ipsum);
+fn short_messages(bencher: &mut Criterion) {
+    let rng = Pcg64Mcg::seed_from_u64(0);
+    let test_input = lipsum_with_rng(rng, 10_000);
+    let messages: Vec<&str> = test_input.split_inclusive('.').collect();
+    let mut group = bencher.benchmark_group("short_messages");
+    group.throughput(Throughput::Bytes(test_input.len() as u64));
+    group.bench_function("translate", |b| {
+        b.iter(|| {
+            for message in &messages {
+                black_box(translate(black_box(message)));
+            }
+        })
+    });
+    group.bench_function("Translator::translate_into", |b| {
+        let mut translator = Translator::default();
+        let mut translated = String::new();
+        b.iter(|| {
+            for message in &messages {
+                translated.clear();
+                translator
+                    .translate_into(black_box(message), &mut translated)
+                    .unwrap();
+                black_box(&translated);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, lorem_ipsum, short_messages);
 criterion_main!(benches);
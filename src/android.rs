@@ -0,0 +1,158 @@
+//! Translating Android string resources (`res/values/strings.xml`).
+
+use crate::escape::{translate_escaped, EscapeSyntax};
+use crate::markup::{translate_keeping_references, Node, Scanner};
+use crate::{PigLatinError, Translator};
+
+/// # Translate Android string resources (`strings.xml`) into Pig-Latin.
+///
+/// Translates the text of `<string>` elements, and of the `<item>`s of `<plurals>` and
+/// `<string-array>` elements. Everything else is kept as-is: resource names, markup,
+/// comments, and the layout of the file. Not translated are:
+///  - resources marked `translatable="false"`,
+///  - references to other resources, such as `@string/app_name`,
+///  - the content of `<xliff:g>` elements, which marks text not to translate.
+///
+/// Escape sequences (such as `\'` or `\n`) are decoded for translation, so that
+/// "Don\'t" is translated as one word, and encoded again in the result. Character
+/// references (such as `&amp;` or `&#233;`) are kept as they are.
+///
+/// Format placeholders, such as `%1$s`, are only kept intact if `translator` protects
+/// them, see [`Protection::with_placeholders`](crate::Protection::with_placeholders).
+///
+/// Returns [`PigLatinError::Syntax`] if `resources` is not well-formed XML.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_android_strings, Protection, Translator};
+/// let resources = r#"<resources>
+///     <string name="app_name" translatable="false">Pig Farm</string>
+///     <string name="greeting">Don\'t feed the <b>%1$s</b> pigs!</string>
+///     <plurals name="pigs">
+///         <item quantity="one">%d pig</item>
+///         <item quantity="other">%d pigs</item>
+///     </plurals>
+/// </resources>"#;
+/// let mut translator =
+///     Translator::default().with_protection(Protection::default().with_placeholders());
+/// assert_eq!(
+///     translate_android_strings(resources, &mut translator).unwrap(),
+///     r#"<resources>
+///     <string name="app_name" translatable="false">Pig Farm</string>
+///     <string name="greeting">On\'tday eedfay ethay <b>%1$s</b> igspay!</string>
+///     <plurals name="pigs">
+///         <item quantity="one">%d igpay</item>
+///         <item quantity="other">%d igspay</item>
+///     </plurals>
+/// </resources>"#
+/// );
+/// ```
+pub fn translate_android_strings(
+    resources: &str,
+    translator: &mut Translator,
+) -> Result<String, PigLatinError> {
+    let mut translated = String::with_capacity(resources.len() * 2);
+    // the open elements, and whether their text is translated
+    let mut open: Vec<(&str, bool)> = Vec::new();
+    let mut scanner = Scanner::new(resources);
+    while let Some(node) = scanner.next() {
+        match node? {
+            Node::Text(text) if open.last().is_some_and(|&(_, translate)| translate) => {
+                let trimmed = text.trim_start();
+                if trimmed.starts_with(['@', '?']) && !trimmed.contains(char::is_whitespace) {
+                    translated.push_str(text);
+                    continue;
+                }
+                translate_keeping_references(text, &mut translated, |piece, output| {
+                    translate_escaped(piece, EscapeSyntax::Android, translator, output);
+                });
+            }
+            Node::StartTag(tag) => {
+                translated.push_str(tag.raw);
+                if tag.self_closing {
+                    continue;
+                }
+                let parent = open.last().copied();
+                let translate = match tag.name {
+                    "string" | "plurals" | "string-array" => {
+                        tag.attribute("translatable") != Some("false")
+                    }
+                    "item" => matches!(parent, Some(("plurals" | "string-array", true))),
+                    "xliff:g" => false,
+                    // formatting, such as <b>, within a string
+                    _ => parent.is_some_and(|(name, translate)| {
+                        translate && !matches!(name, "plurals" | "string-array")
+                    }),
+                };
+                open.push((tag.name, translate));
+            }
+            Node::EndTag(tag) => {
+                match open.pop() {
+                    Some((name, _)) if name == tag.name => {}
+                    _ => {
+                        let start = scanner.pos() - tag.raw.len();
+                        return Err(scanner.error_at(start, &format!("unexpected {}", tag.raw)));
+                    }
+                }
+                translated.push_str(tag.raw);
+            }
+            Node::Text(raw) | Node::Other(raw) => translated.push_str(raw),
+        }
+    }
+    if let Some((name, _)) = open.last() {
+        return Err(scanner.error_at(resources.len(), &format!("unclosed <{name}>")));
+    }
+    Ok(translated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Protection;
+
+    fn translate(resources: &str) -> Result<String, PigLatinError> {
+        let protection = Protection::default().with_placeholders();
+        translate_android_strings(
+            resources,
+            &mut Translator::default().with_protection(protection),
+        )
+    }
+
+    #[test]
+    fn arrays_and_references() {
+        let resources = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!-- Pigs -->\n<resources xmlns:xliff=\"urn:oasis:names:tc:xliff:document:1.2\">\n  <string-array name=\"pigs\">\n    <item>Pink pig</item>\n    <item>@string/other_pig</item>\n  </string-array>\n  <integer-array name=\"sizes\"><item>1</item></integer-array>\n  <string-array name=\"ids\" translatable=\"false\"><item>pig</item></string-array>\n  <string name=\"alias\">@string/pig</string>\n  <string name=\"empty\"/>\n</resources>\n";
+        assert_eq!(
+            translate(resources).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!-- Pigs -->\n<resources xmlns:xliff=\"urn:oasis:names:tc:xliff:document:1.2\">\n  <string-array name=\"pigs\">\n    <item>Inkpay igpay</item>\n    <item>@string/other_pig</item>\n  </string-array>\n  <integer-array name=\"sizes\"><item>1</item></integer-array>\n  <string-array name=\"ids\" translatable=\"false\"><item>pig</item></string-array>\n  <string name=\"alias\">@string/pig</string>\n  <string name=\"empty\"/>\n</resources>\n"
+        );
+    }
+
+    #[test]
+    fn escapes_entities_and_xliff() {
+        let resources = r#"<resources><string name="a">Fish &amp; chips &lt;3 &#233;t&#xE9; &amp;amp; &gt; \"here\"\n<xliff:g id="n" example="5">%d</xliff:g> left, <xliff:g id="x">keep me</xliff:g></string></resources>"#;
+        assert_eq!(
+            translate(resources).unwrap(),
+            r#"<resources><string name="a">Ishfay &amp; ipschay &lt;3 &#233;t&#xE9;hay &amp;amphay; &gt; \"erehay\"\n<xliff:g id="n" example="5">%d</xliff:g> eftlay, <xliff:g id="x">keep me</xliff:g></string></resources>"#
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        for (resources, line) in [
+            (
+                "<resources>\n<string name=\"a\">Pig</strin>\n</resources>",
+                2,
+            ),
+            ("<resources>\n<string name=\"a\">Pig", 2),
+            ("<resources>\n<string name=\"a\"", 2),
+        ] {
+            match translate(resources) {
+                Err(PigLatinError::Syntax {
+                    line: error_line, ..
+                }) => assert_eq!(error_line, line, "{resources:?}"),
+                other => panic!("expected a syntax error for {resources:?}, got {other:?}"),
+            }
+        }
+    }
+}
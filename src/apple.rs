@@ -0,0 +1,491 @@
+//! Translating Apple string resources (`.strings`, `.stringsdict` and `.xcstrings` files).
+
+use crate::escape::{translate_escaped, EscapeSyntax};
+use crate::html::translate_text;
+use std::ops::Range;
+
+use crate::json::{push_quoted, translate_json_values, unquote, value_ranges, ObjectLayout};
+use crate::markup::{decode_entities, Node, Scanner};
+use crate::{PigLatinError, Translator};
+
+/// # Translate Apple string resources (a `.strings` file) into Pig-Latin.
+///
+/// Translates the values of the `"key" = "value";` pairs. Everything else is kept
+/// as-is: keys, comments, and the layout of the file.
+///
+/// Escape sequences (such as `\"` or `\n`) are decoded for translation, and encoded
+/// again in the result.
+///
+/// Format placeholders, such as `%@` or `%1$d`, are only kept intact if `translator`
+/// protects them, see [`Protection::with_placeholders`](crate::Protection::with_placeholders).
+///
+/// Returns [`PigLatinError::Syntax`] if `strings` is not a well-formed `.strings` file.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_apple_strings, Protection, Translator};
+/// let strings = r#"/* The greeting */
+/// "greeting" = "Hello, %@!";
+/// pigs = "\"Oink\", said the pig";
+/// "#;
+/// let mut translator =
+///     Translator::default().with_protection(Protection::default().with_placeholders());
+/// assert_eq!(
+///     translate_apple_strings(strings, &mut translator).unwrap(),
+///     r#"/* The greeting */
+/// "greeting" = "Ellohay, %@!";
+/// pigs = "\"Oinkhay\", aidsay ethay igpay";
+/// "#
+/// );
+/// ```
+pub fn translate_apple_strings(
+    strings: &str,
+    translator: &mut Translator,
+) -> Result<String, PigLatinError> {
+    let mut translated = String::with_capacity(strings.len() * 2);
+    let mut pos = 0;
+    let error = |pos: usize, message: &str| PigLatinError::Syntax {
+        line: strings[..pos].matches('\n').count() + 1,
+        message: message.to_string(),
+    };
+    // what comes next: a key, "=", a value, or ";"
+    let mut expected = 0;
+    loop {
+        let skipped = skip_blanks_and_comments(&strings[pos..])
+            .ok_or_else(|| error(pos, "unterminated comment"))?;
+        translated.push_str(&strings[pos..pos + skipped]);
+        pos += skipped;
+        let rest = &strings[pos..];
+        let Some(next) = rest.chars().next() else {
+            return match expected {
+                0 => Ok(translated),
+                _ => Err(error(pos, "unexpected end of file")),
+            };
+        };
+        let len = match (expected, next) {
+            (0 | 2, '"') => {
+                let len = quoted_len(rest).ok_or_else(|| error(pos, "unterminated string"))?;
+                if expected == 2 {
+                    translated.push('"');
+                    translate_escaped(
+                        &rest[1..len - 1],
+                        EscapeSyntax::Apple,
+                        translator,
+                        &mut translated,
+                    );
+                    translated.push('"');
+                    pos += len;
+                    expected = 3;
+                    continue;
+                }
+                len
+            }
+            (0, c) if c.is_alphanumeric() || c == '_' => rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-')))
+                .unwrap_or(rest.len()),
+            (1, '=') | (3, ';') => 1,
+            (0, _) => return Err(error(pos, "expected a key")),
+            (1, _) => return Err(error(pos, "expected \"=\"")),
+            (2, _) => return Err(error(pos, "expected a quoted value")),
+            _ => return Err(error(pos, "expected \";\"")),
+        };
+        translated.push_str(&rest[..len]);
+        pos += len;
+        expected = (expected + 1) % 4;
+    }
+}
+
+/// Return the length of the whitespace and comments `text` starts with, or `None` if
+/// a comment is not terminated.
+fn skip_blanks_and_comments(text: &str) -> Option<usize> {
+    let mut pos = 0;
+    loop {
+        let rest = &text[pos..];
+        let trimmed = rest.trim_start();
+        pos += rest.len() - trimmed.len();
+        if let Some(comment) = trimmed.strip_prefix("/*") {
+            pos += 2 + comment.find("*/")? + 2;
+        } else if trimmed.starts_with("//") {
+            pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        } else {
+            return Some(pos);
+        }
+    }
+}
+
+/// Return the length of the quoted string `text` starts with, including its quotes.
+fn quoted_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(idx + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// # Translate Apple plural rules (a `.stringsdict` file) into Pig-Latin.
+///
+/// Translates the `<string>` values of the property list, except those of the
+/// `NSStringFormatSpecTypeKey` and `NSStringFormatValueTypeKey` keys, which are not text.
+/// Everything else is kept as-is: keys, markup, comments, and the layout of the file.
+///
+/// Character references (such as `&amp;`) are kept as they are.
+///
+/// Format placeholders, such as `%d` and the variables `%#@pigs@`, are only kept intact
+/// if `translator` protects them, see
+/// [`Protection::with_placeholders`](crate::Protection::with_placeholders).
+///
+/// Returns [`PigLatinError::Syntax`] if `plist` is not well-formed XML.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_stringsdict, Protection, Translator};
+/// let plist = r#"<plist version="1.0"><dict>
+///   <key>%d pigs</key>
+///   <dict>
+///     <key>NSStringLocalizedFormatKey</key>
+///     <string>You have %#@pigs@</string>
+///     <key>pigs</key>
+///     <dict>
+///       <key>NSStringFormatSpecTypeKey</key>
+///       <string>NSStringPluralRuleType</string>
+///       <key>NSStringFormatValueTypeKey</key>
+///       <string>d</string>
+///       <key>one</key>
+///       <string>one pig</string>
+///       <key>other</key>
+///       <string>%d pigs</string>
+///     </dict>
+///   </dict>
+/// </dict></plist>"#;
+/// let mut translator =
+///     Translator::default().with_protection(Protection::default().with_placeholders());
+/// let translated = translate_stringsdict(plist, &mut translator).unwrap();
+/// assert!(translated.contains("<string>Ouyay avehay %#@pigs@</string>"));
+/// assert!(translated.contains("<string>NSStringPluralRuleType</string>"));
+/// assert!(translated.contains("<string>d</string>"));
+/// assert!(translated.contains("<string>onehay igpay</string>"));
+/// assert!(translated.contains("<string>%d igspay</string>"));
+/// ```
+pub fn translate_stringsdict(
+    plist: &str,
+    translator: &mut Translator,
+) -> Result<String, PigLatinError> {
+    let mut translated = String::with_capacity(plist.len() * 2);
+    let mut open: Vec<&str> = Vec::new();
+    let mut key = String::new();
+    let mut scanner = Scanner::new(plist);
+    while let Some(node) = scanner.next() {
+        match node? {
+            Node::Text(text) if open.last() == Some(&"key") => {
+                key.push_str(text);
+                translated.push_str(text);
+            }
+            Node::Text(text)
+                if open.last() == Some(&"string")
+                    && !matches!(
+                        decode_entities(&key).trim(),
+                        "NSStringFormatSpecTypeKey" | "NSStringFormatValueTypeKey"
+                    ) =>
+            {
+                translate_text(text, translator, &mut translated);
+            }
+            Node::StartTag(tag) => {
+                translated.push_str(tag.raw);
+                if !tag.self_closing {
+                    if tag.name == "key" {
+                        key.clear();
+                    }
+                    open.push(tag.name);
+                }
+            }
+            Node::EndTag(tag) => {
+                if open.pop() != Some(tag.name) {
+                    let start = scanner.pos() - tag.raw.len();
+                    return Err(scanner.error_at(start, &format!("unexpected {}", tag.raw)));
+                }
+                translated.push_str(tag.raw);
+            }
+            Node::Text(raw) | Node::Other(raw) => translated.push_str(raw),
+        }
+    }
+    if let Some(name) = open.last() {
+        return Err(scanner.error_at(plist.len(), &format!("unclosed <{name}>")));
+    }
+    Ok(translated)
+}
+
+/// # Add a Pig-Latin pseudo-localization to Apple String Catalogs (an `.xcstrings` file).
+///
+/// Adds a localization in `locale` (such as "en-XA") to every string, translated from
+/// the localization in the source language of the catalog: the `value` of its
+/// `stringUnit`, and those of its plural and device variations, and of its
+/// substitutions. Strings without such a localization are translated from their key
+/// (which is the source string). Strings marked `"shouldTranslate" : false` are
+/// skipped.
+///
+/// Everything else is kept as-is, in particular the other localizations. Only an
+/// existing localization in `locale` is replaced, so that the catalog can be
+/// pseudo-localized again after its strings change. New localizations follow the
+/// formatting of the file.
+///
+/// Format placeholders, such as `%@` or `%lld`, are only kept intact if `translator`
+/// protects them, see [`Protection::with_placeholders`](crate::Protection::with_placeholders).
+///
+/// Returns [`PigLatinError::Syntax`] if `catalog` is not well-formed JSON, or has no
+/// `sourceLanguage`.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_xcstrings, Protection, Translator};
+/// let catalog = r#"{
+///   "sourceLanguage" : "en",
+///   "strings" : {
+///     "Hello, %@!" : {
+///       "localizations" : {
+///         "fr" : {
+///           "stringUnit" : { "state" : "translated", "value" : "Bonjour, %@ !" }
+///         }
+///       }
+///     },
+///     "Oink" : {
+///
+///     }
+///   },
+///   "version" : "1.0"
+/// }"#;
+/// let mut translator =
+///     Translator::default().with_protection(Protection::default().with_placeholders());
+/// assert_eq!(
+///     translate_xcstrings(catalog, &mut translator, "en-XA").unwrap(),
+///     r#"{
+///   "sourceLanguage" : "en",
+///   "strings" : {
+///     "Hello, %@!" : {
+///       "localizations" : {
+///         "fr" : {
+///           "stringUnit" : { "state" : "translated", "value" : "Bonjour, %@ !" }
+///         },
+///         "en-XA" : {
+///           "stringUnit" : {
+///             "state" : "translated",
+///             "value" : "Ellohay, %@!"
+///           }
+///         }
+///       }
+///     },
+///     "Oink" : {
+///       "localizations" : {
+///         "en-XA" : {
+///           "stringUnit" : {
+///             "state" : "translated",
+///             "value" : "Oinkhay"
+///           }
+///         }
+///       }
+///     }
+///   },
+///   "version" : "1.0"
+/// }"#
+/// );
+/// ```
+pub fn translate_xcstrings(
+    catalog: &str,
+    translator: &mut Translator,
+    locale: &str,
+) -> Result<String, PigLatinError> {
+    let ranges = value_ranges(catalog)?;
+    let range = |keys: &[&str]| {
+        let path: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+        ranges.get(&path).cloned()
+    };
+    let source_language = range(&["sourceLanguage"])
+        .and_then(|source_language| unquote(&catalog[source_language]))
+        .ok_or_else(|| PigLatinError::Syntax {
+            line: 1,
+            message: String::from("expected a \"sourceLanguage\""),
+        })?;
+    let Some(strings_layout) =
+        range(&["strings"]).and_then(|strings| ObjectLayout::of(&catalog[strings]))
+    else {
+        return Ok(catalog.to_string());
+    };
+    let mut strings: Vec<(&str, Range<usize>)> = ranges
+        .iter()
+        .filter_map(|(path, string)| match path.as_slice() {
+            [strings, key] if strings == "strings" && catalog[string.clone()].starts_with('{') => {
+                Some((key.as_str(), string.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    strings.sort_by_key(|(_, string)| string.start);
+    // the replacements of byte ranges of `catalog`, in order
+    let mut edits = Vec::new();
+    for (key, string) in strings {
+        let range = |keys: &[&str]| range(&[&["strings", key], keys].concat());
+        if range(&["shouldTranslate"]).is_some_and(|should| &catalog[should] == "false") {
+            continue;
+        }
+        let string_layout =
+            ObjectLayout::of(&catalog[string.clone()]).unwrap_or_else(|| strings_layout.nested());
+        let Some(localizations) = range(&["localizations"]) else {
+            let localizations_layout = string_layout.nested();
+            let localization = key_localization(key, &localizations_layout, translator);
+            let localizations = localizations_layout.object(&[(locale, &localization)]);
+            edits.push(add_member(
+                catalog,
+                string,
+                &string_layout,
+                "localizations",
+                &localizations,
+            ));
+            continue;
+        };
+        let localizations_layout = ObjectLayout::of(&catalog[localizations.clone()])
+            .unwrap_or_else(|| string_layout.nested());
+        let localization = match range(&["localizations", &source_language]) {
+            Some(source) => translate_json_values(
+                &catalog[source],
+                translator,
+                &|path| matches!(path, [.., unit, value] if unit == "stringUnit" && value == "value"),
+            )?,
+            None => key_localization(key, &localizations_layout, translator),
+        };
+        match range(&["localizations", locale]) {
+            Some(existing) => edits.push((existing, localization)),
+            None => edits.push(add_member(
+                catalog,
+                localizations,
+                &localizations_layout,
+                locale,
+                &localization,
+            )),
+        }
+    }
+    let mut translated = String::with_capacity(catalog.len() * 2);
+    let mut pos = 0;
+    for (range, replacement) in edits {
+        translated.push_str(&catalog[pos..range.start]);
+        translated.push_str(&replacement);
+        pos = range.end;
+    }
+    translated.push_str(&catalog[pos..]);
+    Ok(translated)
+}
+
+/// Return a localization of a string catalog with the translation of `key`, to be
+/// added to a `localizations` object laid out like `layout`.
+fn key_localization(key: &str, layout: &ObjectLayout, translator: &mut Translator) -> String {
+    let mut value = String::new();
+    push_quoted(&mut value, &translator.translate(key), &[]);
+    let localization_layout = layout.nested();
+    let unit = localization_layout
+        .nested()
+        .object(&[("state", "\"translated\""), ("value", &value)]);
+    localization_layout.object(&[("stringUnit", &unit)])
+}
+
+/// Return the edit of `catalog` adding the member `"key": value` to the object at
+/// `object`, which is laid out like `layout`.
+fn add_member(
+    catalog: &str,
+    object: Range<usize>,
+    layout: &ObjectLayout,
+    key: &str,
+    value: &str,
+) -> (Range<usize>, String) {
+    let mut member = String::new();
+    let members = &catalog[object.start + 1..object.end - 1];
+    let end = object.start + 1 + members.trim_end().len();
+    if end == object.start + 1 {
+        // replace the whitespace of an empty object
+        layout.push_member(&mut member, key, value);
+        member.push_str(&layout.close);
+        return (end..object.end - 1, member);
+    }
+    member.push(',');
+    layout.push_member(&mut member, key, value);
+    (end..end, member)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Protection;
+
+    fn translator() -> Translator {
+        Translator::default().with_protection(Protection::default().with_placeholders())
+    }
+
+    #[test]
+    fn strings_layout() {
+        let strings = "// Pigs\n\"a\"=\"Pig\";\n  b.title = \"A pig\\nand %1$@\" ; /* two */\n\n\"multi\nline\" = \"Line one\nline two\";";
+        assert_eq!(
+            translate_apple_strings(strings, &mut translator()).unwrap(),
+            "// Pigs\n\"a\"=\"Igpay\";\n  b.title = \"Ahay igpay\\nandhay %1$@\" ; /* two */\n\n\"multi\nline\" = \"Inelay onehay\ninelay otway\";"
+        );
+    }
+
+    #[test]
+    fn strings_syntax_errors() {
+        for (strings, line) in [
+            ("\"a\" = \"b\"\n\"c\" = \"d\";", 2),
+            ("\"a\" \"b\";", 1),
+            ("\"a\" = b;", 1),
+            ("\"a\" = \"b;\n", 1),
+            ("\"a\" =\n", 2),
+            ("/* open\n\"a\" = \"b\";", 1),
+        ] {
+            match translate_apple_strings(strings, &mut translator()) {
+                Err(PigLatinError::Syntax {
+                    line: error_line, ..
+                }) => assert_eq!(error_line, line, "{strings:?}"),
+                other => panic!("expected a syntax error for {strings:?}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn stringsdict_entities() {
+        let plist = "<?xml version=\"1.0\"?>\n<!DOCTYPE plist>\n<plist><dict><key>a</key><string>Fish &amp; chips &#233;t&#233;</string><key>NSStringFormatValueTypeKey</key><string>lld</string></dict></plist>\n";
+        assert_eq!(
+            translate_stringsdict(plist, &mut translator()).unwrap(),
+            "<?xml version=\"1.0\"?>\n<!DOCTYPE plist>\n<plist><dict><key>a</key><string>Ishfay &amp; ipschay &#233;t&#233;hay</string><key>NSStringFormatValueTypeKey</key><string>lld</string></dict></plist>\n"
+        );
+        assert!(matches!(
+            translate_stringsdict("<plist>\n<dict></plist>", &mut translator()),
+            Err(PigLatinError::Syntax { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn xcstrings_variations() {
+        let catalog = r#"{"sourceLanguage":"en","strings":{"%lld pigs":{"localizations":{"en":{"variations":{"plural":{"one":{"stringUnit":{"state":"new","value":"%lld pig"}},"other":{"stringUnit":{"state":"new","value":"%lld pigs"}}}}},"fr":{"substitutions":{"n":{"formatSpecifier":"lld","variations":{"plural":{"other":{"stringUnit":{"state":"translated","value":"cochons"}}}}}},"stringUnit":{"state":"translated","value":"%#@n@"}}}},"value":{"extractionState":"stale"}},"version":"1.0"}"#;
+        assert_eq!(
+            translate_xcstrings(catalog, &mut translator(), "en-XA").unwrap(),
+            r#"{"sourceLanguage":"en","strings":{"%lld pigs":{"localizations":{"en":{"variations":{"plural":{"one":{"stringUnit":{"state":"new","value":"%lld pig"}},"other":{"stringUnit":{"state":"new","value":"%lld pigs"}}}}},"fr":{"substitutions":{"n":{"formatSpecifier":"lld","variations":{"plural":{"other":{"stringUnit":{"state":"translated","value":"cochons"}}}}}},"stringUnit":{"state":"translated","value":"%#@n@"}},"en-XA":{"variations":{"plural":{"one":{"stringUnit":{"state":"new","value":"%lld igpay"}},"other":{"stringUnit":{"state":"new","value":"%lld igspay"}}}}}}},"value":{"extractionState":"stale","localizations":{"en-XA":{"stringUnit":{"state":"translated","value":"aluevay"}}}}},"version":"1.0"}"#
+        );
+    }
+
+    #[test]
+    fn xcstrings_pseudo_locale() {
+        let catalog = r#"{ "sourceLanguage" : "en", "strings" : { "" : { }, "id" : { "shouldTranslate" : false }, "pig" : { "localizations" : { "en-XA" : { "stringUnit" : { "state" : "translated", "value" : "old" } }, "en" : { "stringUnit" : { "state" : "translated", "value" : "Pig" } } } } } }"#;
+        assert_eq!(
+            translate_xcstrings(catalog, &mut translator(), "en-XA").unwrap(),
+            r#"{ "sourceLanguage" : "en", "strings" : { "" : { "localizations" : { "en-XA" : { "stringUnit" : { "state" : "translated", "value" : "" } } } }, "id" : { "shouldTranslate" : false }, "pig" : { "localizations" : { "en-XA" : { "stringUnit" : { "state" : "translated", "value" : "Igpay" } }, "en" : { "stringUnit" : { "state" : "translated", "value" : "Pig" } } } } } }"#
+        );
+        assert!(matches!(
+            translate_xcstrings(r#"{"strings":{}}"#, &mut translator(), "en-XA"),
+            Err(PigLatinError::Syntax { line: 1, .. })
+        ));
+    }
+}
@@ -1,12 +1,52 @@
-use std::io;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
 
-use pig_latin::translate;
+use pig_latin::{
+    translate_android_strings, translate_apple_strings, translate_fluent, translate_html,
+    translate_json, translate_markdown, translate_po, translate_srt, translate_stringsdict,
+    translate_webvtt, translate_xcstrings, PigLatinError, Protection, Translator,
+    DEFAULT_HTML_ATTRIBUTES,
+};
+
+const USAGE: &str = "\
+Usage: pig [FORMAT] [FILE]
+       pig --format FORMAT [FILE]
+       pig json --keys GLOB [FILE]
+       pig html --attributes NAMES [FILE]
+       pig xcstrings --locale LOCALE [FILE]
+
+Translates FILE (or stdin, if FILE is missing or \"-\") into Pig-Latin, printing
+the translation to stdout. Without --format, a first argument naming a format is
+the format, not the FILE: to translate a file called \"json\" as text, use
+\"pig text json\" or \"pig ./json\".
+
+Formats:
+  text          plain text, translated as it arrives (default)
+  po, pot       gettext catalogs: fills in every msgstr with the translation of its msgid
+  fluent, ftl   Project Fluent resources: translates the text of messages and terms
+  json          JSON documents: translates string values, or only those whose key path
+                (such as \"menu.items.0.label\") matches GLOB, where * matches any part
+                of a key, and ** any number of keys (including none)
+  android       Android string resources (strings.xml)
+  strings       Apple string resources (.strings)
+  stringsdict   Apple plural rules (.stringsdict)
+  xcstrings     Apple String Catalogs (.xcstrings): adds a localization in LOCALE
+                (default: en-XA), translated from the source language, to every string
+  html          HTML documents: translates text, except code, and the attributes NAMES
+                (comma-separated, default: title,alt,placeholder,aria-label)
+  markdown, md  Markdown documents: translates prose, but not code, link destinations
+                or front matter
+  srt           SubRip subtitles: translates the text of cues, keeping their timings
+  vtt, webvtt   WebVTT subtitles: translates the text of cues, keeping their timings,
+                settings and notes
+";
 
 /// # Translate all lines recieved from stdin
 ///
-/// Reads from stdin until end of file (EOF) then translates all of it,
-/// printing the translation to stdout. Useful to translate large volumes
-/// of text quickly.
+/// Reads from stdin until end of file (EOF), translating the input as it
+/// arrives and printing the translation to stdout. Useful to translate large
+/// volumes of text quickly: the input is never held in memory all at once.
 ///
 /// ## Usage
 ///
@@ -22,28 +62,291 @@ use pig_latin::translate;
 /// Isthay ishay ahay econdsay inelay. Isthay ishay illstay ethay econdsay inelay.
 /// Evenhay oremay ineslay. Ihay onderway ifhay erethay ishay ahay imitlay ...
 /// ```
+///
+/// ## File formats
+///
+/// Given a format, and optionally a file to read instead of stdin, `pig` translates
+/// only the text of that format, keeping its structure and format placeholders:
+///
+/// ```shell
+/// $ pig po messages.pot > pig_latin.po
+/// $ pig --format po < messages.pot > pig_latin.po
+/// ```
+///
+/// See `pig --help` for all formats.
+///
 /// ## Translation details
 ///
 /// See the library crate [`pig_latin`]
-fn main() -> io::Result<()> {
-    let input_text = read_all_stdin()?;
-    let translated = translate(&input_text);
-    println!("{translated}");
-    Ok(())
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("pig: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            match &args.path {
+                Some(path) => eprintln!("pig: {path}: {error}"),
+                None => eprintln!("pig: {error}"),
+            }
+            ExitCode::FAILURE
+        }
+    }
 }
 
-/// # Read every line from stdin into a new string buffer and return it
-///
-/// The read loop only ends when reaching EOF (ctrl-Z on windows)
-fn read_all_stdin() -> io::Result<String> {
-    let stdin = io::stdin();
-    let mut input_text = String::new();
-    loop {
-        match stdin.read_line(&mut input_text) {
-            Ok(0) => break,
-            Err(error) => return Err(error),
-            Ok(_) => continue,
+/// The file formats `pig` can translate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Po,
+    Fluent,
+    Json,
+    Android,
+    AppleStrings,
+    Stringsdict,
+    Xcstrings,
+    Html,
+    Markdown,
+    Srt,
+    WebVtt,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" | "txt" => Some(Format::Text),
+            "po" | "pot" => Some(Format::Po),
+            "fluent" | "ftl" => Some(Format::Fluent),
+            "json" => Some(Format::Json),
+            "android" => Some(Format::Android),
+            "strings" => Some(Format::AppleStrings),
+            "stringsdict" => Some(Format::Stringsdict),
+            "xcstrings" => Some(Format::Xcstrings),
+            "html" | "htm" => Some(Format::Html),
+            "markdown" | "md" => Some(Format::Markdown),
+            "srt" => Some(Format::Srt),
+            "vtt" | "webvtt" => Some(Format::WebVtt),
+            _ => None,
+        }
+    }
+}
+
+/// The command line arguments.
+#[derive(Debug)]
+struct Args {
+    format: Format,
+    /// The file to translate, or `None` for stdin.
+    path: Option<String>,
+    /// The glob of the keys to translate, for JSON.
+    keys: Option<String>,
+    /// The attributes to translate, for HTML.
+    attributes: Option<String>,
+    /// The locale of the pseudo-localization, for String Catalogs.
+    locale: Option<String>,
+}
+
+impl Args {
+    /// Parse the command line arguments, returning `None` if help was requested.
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut format = None;
+        let mut keys = None;
+        let mut attributes = None;
+        let mut locale = None;
+        let mut positional = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "-f" | "--format" => {
+                    let name = args.next().ok_or("missing format after --format")?;
+                    format = Some(parse_format(&name)?);
+                }
+                _ if arg.starts_with("--format=") => {
+                    format = Some(parse_format(&arg["--format=".len()..])?);
+                }
+                "-k" | "--keys" => {
+                    keys = Some(args.next().ok_or("missing glob after --keys")?);
+                }
+                _ if arg.starts_with("--keys=") => {
+                    keys = Some(arg["--keys=".len()..].to_string());
+                }
+                "-a" | "--attributes" => {
+                    attributes = Some(args.next().ok_or("missing names after --attributes")?);
+                }
+                _ if arg.starts_with("--attributes=") => {
+                    attributes = Some(arg["--attributes=".len()..].to_string());
+                }
+                "-l" | "--locale" => {
+                    locale = Some(args.next().ok_or("missing locale after --locale")?);
+                }
+                _ if arg.starts_with("--locale=") => {
+                    locale = Some(arg["--locale=".len()..].to_string());
+                }
+                _ if arg.starts_with('-') && arg != "-" => {
+                    return Err(format!("unknown option {arg:?}"))
+                }
+                _ => positional.push(arg),
+            }
+        }
+        let mut positional = positional.into_iter();
+        if format.is_none() {
+            // a leading format name, even if a file has the same name
+            if let Some(named) = positional
+                .as_slice()
+                .first()
+                .and_then(|first| Format::from_name(first))
+            {
+                format = Some(named);
+                positional.next();
+            }
+        }
+        let path = positional.next().filter(|path| path != "-");
+        if let Some(extra) = positional.next() {
+            return Err(format!("unexpected argument {extra:?}"));
+        }
+        let format = format.unwrap_or(Format::Text);
+        if keys.is_some() && format != Format::Json {
+            return Err(String::from("--keys is only supported for json"));
+        }
+        if attributes.is_some() && format != Format::Html {
+            return Err(String::from("--attributes is only supported for html"));
+        }
+        if locale.is_some() && format != Format::Xcstrings {
+            return Err(String::from("--locale is only supported for xcstrings"));
+        }
+        Ok(Some(Args {
+            format,
+            path,
+            keys,
+            attributes,
+            locale,
+        }))
+    }
+
+    /// Translate `input`, given in the format of the arguments.
+    fn translate(&self, input: &str, translator: &mut Translator) -> Result<String, PigLatinError> {
+        match self.format {
+            Format::Text => Ok(translator.translate(input)),
+            Format::Po => translate_po(input, translator),
+            Format::Fluent => translate_fluent(input, translator),
+            Format::Json => translate_json(input, translator, self.keys.as_deref()),
+            Format::Android => translate_android_strings(input, translator),
+            Format::AppleStrings => translate_apple_strings(input, translator),
+            Format::Stringsdict => translate_stringsdict(input, translator),
+            Format::Xcstrings => {
+                translate_xcstrings(input, translator, self.locale.as_deref().unwrap_or("en-XA"))
+            }
+            Format::Html => match &self.attributes {
+                Some(names) => {
+                    let attributes: Vec<&str> = names
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .collect();
+                    translate_html(input, translator, &attributes)
+                }
+                None => translate_html(input, translator, DEFAULT_HTML_ATTRIBUTES),
+            },
+            Format::Markdown => Ok(translate_markdown(input, translator)),
+            Format::Srt => translate_srt(input, translator),
+            Format::WebVtt => translate_webvtt(input, translator),
+        }
+    }
+}
+
+fn parse_format(name: &str) -> Result<Format, String> {
+    Format::from_name(name).ok_or_else(|| format!("unknown format {name:?}"))
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    if args.format == Format::Text {
+        let mut translator = Translator::default();
+        return match &args.path {
+            Some(path) => {
+                let file = io::BufReader::new(fs::File::open(path)?);
+                Ok(translator.translate_stream(file, io::stdout().lock())?)
+            }
+            None => Ok(translator.translate_stream(io::stdin().lock(), io::stdout().lock())?),
+        };
+    }
+
+    let input = match &args.path {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let mut translator =
+        Translator::default().with_protection(Protection::default().with_placeholders());
+    let translated = args.translate(&input, &mut translator)?;
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(translated.as_bytes())?;
+    Ok(stdout.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Args>, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn format_and_file() {
+        for (args, format, path) in [
+            (&[][..], Format::Text, None),
+            (&["pigs.txt"], Format::Text, Some("pigs.txt")),
+            (&["json"], Format::Json, None),
+            (&["json", "-"], Format::Json, None),
+            (&["po", "json"], Format::Po, Some("json")),
+            (&["--format", "md", "json"], Format::Markdown, Some("json")),
+            (&["--format=vtt", "-"], Format::WebVtt, None),
+            (&["./json"], Format::Text, Some("./json")),
+        ] {
+            let parsed = parse(args).unwrap().unwrap();
+            assert_eq!(parsed.format, format, "{args:?}");
+            assert_eq!(parsed.path.as_deref(), path, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn options() {
+        let args = parse(&["json", "--keys", "**.title", "en.json"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(args.keys.as_deref(), Some("**.title"));
+        assert_eq!(args.path.as_deref(), Some("en.json"));
+        let args = parse(&["--attributes=title,alt", "html"]).unwrap().unwrap();
+        assert_eq!(args.attributes.as_deref(), Some("title,alt"));
+        let args = parse(&["xcstrings", "-l", "qps"]).unwrap().unwrap();
+        assert_eq!(args.locale.as_deref(), Some("qps"));
+        assert!(parse(&["po", "--help"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn errors() {
+        for args in [
+            &["--format"][..],
+            &["--format", "doc"],
+            &["--keys", "a"],
+            &["po", "--attributes", "title"],
+            &["--locale", "qps"],
+            &["--verbose"],
+            &["a.txt", "b.txt"],
+            &["json", "a.json", "b.json"],
+        ] {
+            assert!(parse(args).is_err(), "{args:?}");
         }
     }
-    Ok(input_text)
 }
@@ -0,0 +1,332 @@
+//! Dialects of Pig-Latin, other than (but defaulting to) the One True Dialect.
+
+use std::sync::LazyLock;
+
+use unicode_normalization::char::decompose_canonical;
+
+/// The rules of a Pig-Latin dialect.
+///
+/// The [`Default`] dialect is the [One True Dialect](crate#one-true-dialect)
+/// (OTDoPL). Other dialects are built using a [`DialectBuilder`], and used
+/// with [`translate_with`](crate::translate_with),
+/// [`translate_word_with`](crate::translate_word_with) and
+/// [`untranslate_with`](crate::untranslate_with).
+///
+/// ## Examples
+///
+/// A dialect using "way" for words starting with a vowel, without the "qu" rule:
+/// ```rust
+/// # use pig_latin::{translate_with, Dialect};
+/// let dialect = Dialect::builder().vowel_suffix("way").no_onset_units().build();
+/// assert_eq!(
+///     translate_with("Ask the queen", &dialect),
+///     String::from("Askway ethay ueenqay")
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dialect {
+    vowel_suffix: String,
+    consonant_suffix: String,
+    onset_units: Vec<String>,
+    vowels: Vec<char>,
+    contractions: bool,
+    vowelless: VowellessPolicy,
+    y_mode: YMode,
+}
+
+/// Whether a [`Dialect`] treats "y" as a vowel or as a consonant.
+///
+/// If "y" is one of the dialect's [vowels](Dialect::vowels), it is always a vowel,
+/// regardless of this setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum YMode {
+    /// "y" is always a consonant: "style" becomes "estylay" (OTDoPL).
+    #[default]
+    Consonant,
+    /// "y" is a consonant at the start of a word, and a vowel elsewhere: "yellow"
+    /// becomes "ellowyay", whereas "style" becomes "ylestay" and "rhythm" becomes "ythmrhay".
+    Positional,
+}
+
+/// How a [`Dialect`] translates words without any vowel, such as "rhythm" or "psst".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VowellessPolicy {
+    /// Move the whole word in front of the consonant suffix: "rhythm" becomes
+    /// "rhythmay" (OTDoPL).
+    #[default]
+    WholeWord,
+    /// Treat the first "y" that does not start the word as its first vowel: "rhythm"
+    /// becomes "ythmrhay". Words without such a "y" are translated like
+    /// [`VowellessPolicy::WholeWord`].
+    YAsVowel,
+    /// Leave the word untranslated: "rhythm" stays "rhythm".
+    Untouched,
+}
+
+impl Dialect {
+    /// Start building a new dialect, beginning with the rules of OTDoPL.
+    pub fn builder() -> DialectBuilder {
+        DialectBuilder {
+            dialect: Dialect::default(),
+        }
+    }
+
+    /// The suffix for words starting with a vowel.
+    pub fn vowel_suffix(&self) -> &str {
+        &self.vowel_suffix
+    }
+
+    /// The suffix for words starting with a consonant.
+    pub fn consonant_suffix(&self) -> &str {
+        &self.consonant_suffix
+    }
+
+    /// The (lower case) letter sequences that are moved as a whole if they start a
+    /// word, like "qu", even if they contain vowels.
+    pub fn onset_units(&self) -> &[String] {
+        &self.onset_units
+    }
+
+    /// The (lower case) characters that are treated as vowels.
+    ///
+    /// Letters with diacritics are vowels if their base letter is, e.g. "é", "Ü" and "å"
+    /// are vowels if "e", "u" and "a" are.
+    pub fn vowels(&self) -> &[char] {
+        &self.vowels
+    }
+
+    /// Whether words joined by an apostrophe (such as "don't") are translated as one word.
+    ///
+    /// If so, a possessive "'s" at the end of a word is kept at the end of its
+    /// translation ("pig's" becomes "igpay's").
+    pub fn contractions(&self) -> bool {
+        self.contractions
+    }
+
+    /// How words without any vowel are translated.
+    pub fn vowelless(&self) -> VowellessPolicy {
+        self.vowelless
+    }
+
+    /// Whether "y" is a vowel or a consonant.
+    pub fn y_mode(&self) -> YMode {
+        self.y_mode
+    }
+
+    /// Return `true` if `c` is a vowel in this dialect when it is the `grapheme_idx`-th
+    /// grapheme cluster of a word, else `false` (uncased).
+    pub(crate) fn is_vowel_at(&self, c: char, grapheme_idx: usize) -> bool {
+        self.is_vowel(c)
+            || (self.y_mode == YMode::Positional && grapheme_idx > 0 && matches!(c, 'y' | 'Y'))
+    }
+
+    /// Return `true` if `c` is a vowel in this dialect, else `false` (uncased).
+    pub(crate) fn is_vowel(&self, c: char) -> bool {
+        let c = lowercase(c);
+        if self.vowels.contains(&c) {
+            return true;
+        }
+        !c.is_ascii() && base_letter(c).is_some_and(|base| self.vowels.contains(&base))
+    }
+}
+
+/// Return the lower case variant of `c`, if it is a single `char`, else `c`.
+fn lowercase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c,
+    }
+}
+
+/// Find the (lower case) base letter of a letter with diacritics, e.g. 'a' for 'Å'.
+///
+/// The base letter is found by canonical decomposition, so it is the same for
+/// precomposed letters and for those followed by combining marks. A few Latin
+/// letters that do not decompose are mapped by hand ('æ', 'ø', 'œ', 'ı').
+/// Returns `None` if `c` has no distinct base letter.
+fn base_letter(c: char) -> Option<char> {
+    let mut base = None;
+    decompose_canonical(c, |part| {
+        base.get_or_insert(part);
+    });
+    match base {
+        Some(base) if base != c => Some(lowercase(base)),
+        _ => match c {
+            'æ' => Some('a'),
+            'ø' | 'œ' => Some('o'),
+            'ı' => Some('i'),
+            _ => None,
+        },
+    }
+}
+
+impl Default for Dialect {
+    /// The [One True Dialect](crate#one-true-dialect).
+    fn default() -> Self {
+        Dialect {
+            vowel_suffix: String::from("hay"),
+            consonant_suffix: String::from("ay"),
+            onset_units: vec![String::from("qu")],
+            vowels: vec!['a', 'e', 'i', 'o', 'u'],
+            contractions: true,
+            vowelless: VowellessPolicy::WholeWord,
+            y_mode: YMode::Consonant,
+        }
+    }
+}
+
+/// The One True Dialect, shared by all functions not taking a [`Dialect`].
+pub(crate) static OTDOPL: LazyLock<Dialect> = LazyLock::new(Dialect::default);
+
+/// A builder for [`Dialect`]s, see [`Dialect::builder`].
+#[derive(Debug, Clone)]
+pub struct DialectBuilder {
+    dialect: Dialect,
+}
+
+impl DialectBuilder {
+    /// Set the suffix for words starting with a vowel (OTDoPL: "hay").
+    pub fn vowel_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.dialect.vowel_suffix = suffix.into();
+        self
+    }
+
+    /// Set the suffix for words starting with a consonant (OTDoPL: "ay").
+    pub fn consonant_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.dialect.consonant_suffix = suffix.into();
+        self
+    }
+
+    /// Add an onset unit (OTDoPL: "qu"), ignoring case.
+    ///
+    /// Words starting with `unit` move it to the back as a whole, together with any
+    /// consonants following it, e.g. "squeal" becomes "ealsquay" with the unit "squ".
+    /// If several units start a word, the longest one is used.
+    pub fn onset_unit(mut self, unit: impl Into<String>) -> Self {
+        let unit = unit
+            .into()
+            .chars()
+            .map(|c| c.to_lowercase().next().unwrap_or(c))
+            .collect();
+        self.dialect.onset_units.push(unit);
+        self
+    }
+
+    /// Remove all onset units, including the "qu" rule of OTDoPL.
+    pub fn no_onset_units(mut self) -> Self {
+        self.dialect.onset_units.clear();
+        self
+    }
+
+    /// Set the characters treated as vowels (OTDoPL: "aeiou"), ignoring case.
+    pub fn vowels(mut self, vowels: &str) -> Self {
+        self.dialect.vowels = vowels
+            .chars()
+            .map(|c| c.to_lowercase().next().unwrap_or(c))
+            .collect();
+        self
+    }
+
+    /// Set whether words joined by an apostrophe are translated as one word (OTDoPL: `true`).
+    ///
+    /// Otherwise, apostrophes are punctuation, and "don't" is translated like "don" and "t".
+    pub fn contractions(mut self, contractions: bool) -> Self {
+        self.dialect.contractions = contractions;
+        self
+    }
+
+    /// Set how words without any vowel are translated (OTDoPL: [`VowellessPolicy::WholeWord`]).
+    pub fn vowelless(mut self, vowelless: VowellessPolicy) -> Self {
+        self.dialect.vowelless = vowelless;
+        self
+    }
+
+    /// Set whether "y" is a vowel or a consonant (OTDoPL: [`YMode::Consonant`]).
+    pub fn y_mode(mut self, y_mode: YMode) -> Self {
+        self.dialect.y_mode = y_mode;
+        self
+    }
+
+    /// Finish building the dialect.
+    pub fn build(self) -> Dialect {
+        self.dialect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_otdopl() {
+        let dialect = Dialect::default();
+        assert_eq!(dialect.vowel_suffix(), "hay");
+        assert_eq!(dialect.consonant_suffix(), "ay");
+        assert_eq!(dialect.onset_units(), ["qu"]);
+        assert_eq!(dialect.vowels(), ['a', 'e', 'i', 'o', 'u']);
+        assert!(dialect.contractions());
+        assert_eq!(dialect.vowelless(), VowellessPolicy::WholeWord);
+        assert_eq!(dialect.y_mode(), YMode::Consonant);
+        assert_eq!(Dialect::builder().build(), dialect);
+    }
+
+    #[test]
+    fn builder() {
+        let dialect = Dialect::builder()
+            .vowel_suffix("yay")
+            .consonant_suffix("ei")
+            .no_onset_units()
+            .onset_unit("GU")
+            .onset_unit("Sch")
+            .vowels("AEIOUY")
+            .contractions(false)
+            .vowelless(VowellessPolicy::Untouched)
+            .y_mode(YMode::Positional)
+            .build();
+        assert_eq!(dialect.vowel_suffix(), "yay");
+        assert_eq!(dialect.consonant_suffix(), "ei");
+        assert_eq!(dialect.onset_units(), ["gu", "sch"]);
+        assert_eq!(dialect.vowels(), ['a', 'e', 'i', 'o', 'u', 'y']);
+        assert!(!dialect.contractions());
+        assert_eq!(dialect.vowelless(), VowellessPolicy::Untouched);
+        assert_eq!(dialect.y_mode(), YMode::Positional);
+    }
+
+    #[test]
+    fn vowels_with_diacritics() {
+        let dialect = Dialect::default();
+        for vowel in "éÉèêëÜüåÅäöÖòóôõœøæıǖ".chars() {
+            assert!(dialect.is_vowel(vowel), "{vowel} should be a vowel");
+        }
+        for consonant in "çÇñÑšßþðŷ\u{301}\u{308}".chars() {
+            assert!(
+                !dialect.is_vowel(consonant),
+                "{consonant} should not be a vowel"
+            );
+        }
+        assert!(Dialect::builder().vowels("aeiouy").build().is_vowel('ŷ'));
+        assert!(Dialect::builder().vowels("é").build().is_vowel('É'));
+        assert!(!Dialect::builder().vowels("é").build().is_vowel('e'));
+    }
+
+    #[test]
+    fn y_positional() {
+        let dialect = Dialect::builder().y_mode(YMode::Positional).build();
+        assert!(!dialect.is_vowel_at('y', 0));
+        assert!(dialect.is_vowel_at('y', 1));
+        assert!(dialect.is_vowel_at('Y', 3));
+        assert!(dialect.is_vowel_at('a', 0));
+        assert!(!Dialect::default().is_vowel_at('y', 1));
+        let dialect = Dialect::builder().vowels("aeiouy").build();
+        assert!(dialect.is_vowel_at('y', 0));
+    }
+
+    #[test]
+    fn vowels_uncased() {
+        let dialect = Dialect::builder().vowels("ay").build();
+        assert!(dialect.is_vowel('a'));
+        assert!(dialect.is_vowel('Y'));
+        assert!(!dialect.is_vowel('e'));
+    }
+}
@@ -0,0 +1,84 @@
+//! The errors of the fallible functions of this crate.
+
+use std::error::Error;
+use std::fmt;
+
+/// The reasons why a text cannot be translated.
+///
+/// These are returned by [`try_translate_word`](crate::try_translate_word), if the text
+/// is not a single word, and by the translators of file formats (such as
+/// [`translate_po`](crate::translate_po)), if the text is not a well-formed file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PigLatinError {
+    /// The input is empty.
+    EmptyInput,
+    /// The input consists of more than one word, or of a word and punctuation or whitespace.
+    EmbeddedSeparator {
+        /// The first character that is not part of the word.
+        separator: char,
+        /// The byte index of `separator` in the input.
+        byte_idx: usize,
+    },
+    /// The input contains no letters, e.g. because it is a number.
+    NoLetters,
+    /// The input is not a well-formed file of the expected format.
+    Syntax {
+        /// The (one-based) line of the error.
+        line: usize,
+        /// What is wrong.
+        message: String,
+    },
+}
+
+impl fmt::Display for PigLatinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PigLatinError::EmptyInput => write!(f, "cannot translate an empty word"),
+            PigLatinError::EmbeddedSeparator {
+                separator,
+                byte_idx,
+            } => write!(
+                f,
+                "expected a single word, found separator {separator:?} at byte {byte_idx}"
+            ),
+            PigLatinError::NoLetters => write!(f, "expected a word, found no letters"),
+            PigLatinError::Syntax { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for PigLatinError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            PigLatinError::EmptyInput.to_string(),
+            "cannot translate an empty word"
+        );
+        assert_eq!(
+            PigLatinError::EmbeddedSeparator {
+                separator: ' ',
+                byte_idx: 5
+            }
+            .to_string(),
+            "expected a single word, found separator ' ' at byte 5"
+        );
+        assert_eq!(
+            PigLatinError::NoLetters.to_string(),
+            "expected a word, found no letters"
+        );
+        assert_eq!(
+            PigLatinError::Syntax {
+                line: 3,
+                message: String::from("unterminated string")
+            }
+            .to_string(),
+            "line 3: unterminated string"
+        );
+    }
+}
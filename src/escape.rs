@@ -0,0 +1,166 @@
+//! Translating strings with backslash escape sequences, as in Android and Apple resources.
+
+use crate::Translator;
+
+/// The escape sequences of a string resource format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EscapeSyntax {
+    /// Android's `strings.xml`: `\'`, `\"`, `\@`, `\?`, `\uXXXX`, ..., where unescaped
+    /// double quotes delimit text whose whitespace is kept.
+    Android,
+    /// Apple's `.strings`: `\"`, `\\`, `\UXXXX`, ...
+    Apple,
+}
+
+/// Translate `text`, in which escape sequences are decoded for translation (so that
+/// "Don\'t" is one word), and encoded again afterwards.
+///
+/// Escaped whitespace ("\n", "\t", "\r"), which separates words anyway, is copied as-is,
+/// as are Android's unescaped quotes. Only translated text is re-encoded, escaping
+/// what needs to be escaped, and writing characters given by a Unicode escape (such
+/// as "\u00e9") with that escape again.
+pub(crate) fn translate_escaped(
+    text: &str,
+    syntax: EscapeSyntax,
+    translator: &mut Translator,
+    output: &mut String,
+) {
+    let mut piece_start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        let separator_len = match c {
+            '\\' => match chars.peek() {
+                Some((_, 'n' | 't' | 'r')) => 2,
+                Some(_) => {
+                    chars.next();
+                    continue;
+                }
+                None => continue,
+            },
+            '"' if syntax == EscapeSyntax::Android => 1,
+            _ => continue,
+        };
+        translate_piece(
+            &text[piece_start..idx],
+            piece_start == 0,
+            syntax,
+            translator,
+            output,
+        );
+        output.push_str(&text[idx..idx + separator_len]);
+        if separator_len == 2 {
+            chars.next();
+        }
+        piece_start = idx + separator_len;
+    }
+    translate_piece(
+        &text[piece_start..],
+        piece_start == 0,
+        syntax,
+        translator,
+        output,
+    );
+}
+
+/// Translate `piece`, which contains no separators; `at_start` if it starts the string.
+fn translate_piece(
+    piece: &str,
+    at_start: bool,
+    syntax: EscapeSyntax,
+    translator: &mut Translator,
+    output: &mut String,
+) {
+    if piece.is_empty() {
+        return;
+    }
+    let (value, escapes) = unescape(piece);
+    let translated = translator.translate(&value);
+    for (idx, c) in translated.char_indices() {
+        if let Some((_, escape)) = escapes.iter().find(|&&(escaped, _)| escaped == c) {
+            output.push_str(escape);
+            continue;
+        }
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '"' => output.push_str("\\\""),
+            '\'' if syntax == EscapeSyntax::Android => output.push_str("\\'"),
+            // resource references
+            '@' | '?' if syntax == EscapeSyntax::Android && at_start && idx == 0 => {
+                output.push('\\');
+                output.push(c);
+            }
+            c => output.push(c),
+        }
+    }
+}
+
+/// Decode the escape sequences of `piece`, keeping unknown or malformed ones as-is.
+///
+/// Also returns the characters given by Unicode escapes, with the escapes as written.
+fn unescape(piece: &str) -> (String, Vec<(char, &str)>) {
+    let mut value = String::with_capacity(piece.len());
+    let mut escapes = Vec::new();
+    let mut rest = piece;
+    while let Some(idx) = rest.find('\\') {
+        value.push_str(&rest[..idx]);
+        let escape = &rest[idx..];
+        rest = &escape[1..];
+        let Some(c) = rest.chars().next() else {
+            value.push('\\');
+            break;
+        };
+        let code_point = rest
+            .get(1..5)
+            .filter(|_| matches!(c, 'u' | 'U'))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32);
+        match (c, code_point) {
+            (_, Some(code_point)) => {
+                value.push(code_point);
+                escapes.push((code_point, &escape[..6]));
+                rest = &rest[5..];
+            }
+            ('\'' | '"' | '\\' | '@' | '?', _) => {
+                value.push(c);
+                rest = &rest[1..];
+            }
+            _ => value.push('\\'),
+        }
+    }
+    value.push_str(rest);
+    (value, escapes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(text: &str, syntax: EscapeSyntax) -> String {
+        let mut output = String::new();
+        translate_escaped(text, syntax, &mut Translator::default(), &mut output);
+        output
+    }
+
+    #[test]
+    fn android() {
+        for (text, expected) in [
+            (r"Don\'t panic\nNow", r"On\'tday anicpay\nOwnay"),
+            (r#"Say \"oink\"\tloudly"#, r#"Aysay \"oinkhay\"\toudlylay"#),
+            (r#""  Spaced  out ""#, r#""  Acedspay  outhay ""#),
+            (r"\@home is été", r"\@omehay ishay étéhay"),
+            (r"back\\slash", r"ackbay\\ashslay"),
+        ] {
+            assert_eq!(translate(text, EscapeSyntax::Android), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn apple() {
+        for (text, expected) in [
+            (r#"Don't say \"oink\"\n"#, r#"On'tday aysay \"oinkhay\"\n"#),
+            (r"\U00e9t\U00e9 @home", r"\U00e9t\U00e9hay @omehay"),
+        ] {
+            assert_eq!(translate(text, EscapeSyntax::Apple), expected, "{text}");
+        }
+    }
+}
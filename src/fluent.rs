@@ -0,0 +1,373 @@
+//! Translating Project Fluent resources (`.ftl` files).
+
+use crate::{PigLatinError, Translator};
+
+/// # Translate a [Fluent](https://projectfluent.org) resource (`.ftl` file) into Pig-Latin.
+///
+/// Only the text of the values and attributes of messages, and of the values of terms,
+/// is translated. The attributes of terms are not, as they are private values used in
+/// selectors (such as `{ -brand.gender -> ... }`). Everything else is kept as-is:
+/// comments, identifiers, placeables (such as `{ $name }`, `{ -brand }` or
+/// `{ NUMBER($count) }`), selectors and variant keys, as well as the layout of the
+/// file. The patterns of variants are translated like any other value.
+///
+/// Returns [`PigLatinError::Syntax`] if `resource` is not a well-formed resource.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_fluent, Translator};
+/// let resource = "\
+/// -brand = Pig Farm
+/// welcome = Welcome to { -brand }, { $user }!
+///     .title = Hello there
+/// pigs = { $count ->
+///     [one] One pig
+///    *[other] { $count } pigs
+/// }
+/// ";
+/// assert_eq!(
+///     translate_fluent(resource, &mut Translator::default()).unwrap(),
+///     "\
+/// -brand = Igpay Armfay
+/// welcome = Elcomeway otay { -brand }, { $user }!
+///     .title = Ellohay erethay
+/// pigs = { $count ->
+///     [one] Onehay igpay
+///    *[other] { $count } igspay
+/// }
+/// "
+/// );
+/// ```
+pub fn translate_fluent(
+    resource: &str,
+    translator: &mut Translator,
+) -> Result<String, PigLatinError> {
+    let mut parser = Parser {
+        input: resource,
+        pos: 0,
+        output: String::with_capacity(resource.len() * 2),
+        translator,
+        translate: true,
+    };
+    parser.resource()?;
+    Ok(parser.output)
+}
+
+/// A parser of Fluent resources, writing their translation to `output` as it goes.
+struct Parser<'a, 't> {
+    input: &'a str,
+    /// The byte index of the next character to parse.
+    pos: usize,
+    output: String,
+    translator: &'t mut Translator,
+    /// Whether the text of patterns is translated, or copied.
+    translate: bool,
+}
+
+impl Parser<'_, '_> {
+    fn resource(&mut self) -> Result<(), PigLatinError> {
+        while self.pos < self.input.len() {
+            let line = self.rest_of_line();
+            if line.starts_with('#') || is_blank(line) {
+                self.copy(line.len());
+                self.copy_line_end();
+            } else if line.starts_with(|c: char| c.is_ascii_alphabetic() || c == '-') {
+                self.entry()?;
+            } else {
+                return Err(self.error("expected a message, a term or a comment"));
+            }
+        }
+        Ok(())
+    }
+
+    /// A message or a term, with its attributes.
+    fn entry(&mut self) -> Result<(), PigLatinError> {
+        let is_term = self.peek() == Some('-');
+        if is_term {
+            self.copy(1);
+        }
+        self.identifier()?;
+        self.assignment()?;
+        self.pattern()?;
+        while self.next_line_starts_with(|c| c == '.') {
+            let indent = self.rest().len() - self.rest().trim_start().len();
+            self.copy(indent + 1);
+            self.identifier()?;
+            self.assignment()?;
+            self.translate = !is_term;
+            let pattern = self.pattern();
+            self.translate = true;
+            pattern?;
+        }
+        self.copy_line_end();
+        Ok(())
+    }
+
+    fn identifier(&mut self) -> Result<(), PigLatinError> {
+        if !self.rest().starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(self.error("expected an identifier"));
+        }
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(self.rest().len());
+        self.copy(len);
+        Ok(())
+    }
+
+    /// The "=" after an identifier, and the blanks around it.
+    fn assignment(&mut self) -> Result<(), PigLatinError> {
+        self.copy_blank_inline();
+        if self.peek() != Some('=') {
+            return Err(self.error("expected \"=\""));
+        }
+        self.copy(1);
+        self.copy_blank_inline();
+        Ok(())
+    }
+
+    /// Text and placeables, until the end of a line that is not continued.
+    fn pattern(&mut self) -> Result<(), PigLatinError> {
+        loop {
+            let start = self.pos;
+            while let Some(c) = self.peek() {
+                match c {
+                    '{' => break,
+                    '}' => return Err(self.error("unexpected \"}\" in text")),
+                    '\n' if !self.next_line_continues_pattern() => break,
+                    c => self.pos += c.len_utf8(),
+                }
+            }
+            let text = &self.input[start..self.pos];
+            if self.translate {
+                self.translator
+                    .translate_into(text, &mut self.output)
+                    .expect("writing to a String should not fail");
+            } else {
+                self.output.push_str(text);
+            }
+            if self.peek() != Some('{') {
+                return Ok(());
+            }
+            self.placeable()?;
+        }
+    }
+
+    /// A placeable, from its "{" to its "}", translating only the patterns of variants.
+    fn placeable(&mut self) -> Result<(), PigLatinError> {
+        let start = self.pos;
+        self.copy(1);
+        while let Some(c) = self.peek() {
+            match c {
+                '}' => {
+                    self.copy(1);
+                    return Ok(());
+                }
+                '{' => self.placeable()?,
+                '"' => self.string_literal()?,
+                '-' if self.rest().starts_with("->") => {
+                    self.copy(2);
+                    self.variants()?;
+                }
+                c => self.copy(c.len_utf8()),
+            }
+        }
+        Err(self.error_at(start, "unterminated placeable"))
+    }
+
+    /// The variants of a select expression, up to its closing "}".
+    fn variants(&mut self) -> Result<(), PigLatinError> {
+        loop {
+            let blank = self.rest().len() - self.rest().trim_start().len();
+            self.copy(blank);
+            match self.peek() {
+                Some('}') | None => return Ok(()),
+                Some('*') => self.copy(1),
+                _ => {}
+            }
+            if self.peek() != Some('[') {
+                return Err(self.error("expected a variant"));
+            }
+            match self.rest_of_line().find(']') {
+                Some(key_end) => self.copy(key_end + 1),
+                None => return Err(self.error("unterminated variant key")),
+            }
+            self.pattern()?;
+        }
+    }
+
+    fn string_literal(&mut self) -> Result<(), PigLatinError> {
+        let mut chars = self.rest_of_line().char_indices().skip(1);
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => {
+                    self.copy(idx + 1);
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        Err(self.error("unterminated string literal"))
+    }
+
+    /// Return `true` if the line after the current one (skipping blank lines)
+    /// continues the current pattern, i.e. is indented, and does not start a variant,
+    /// an attribute, or the end of a placeable.
+    fn next_line_continues_pattern(&self) -> bool {
+        self.next_line_starts_with(|c| !matches!(c, '[' | '*' | '.' | '}'))
+    }
+
+    /// Return `true` if the line after the current one (skipping blank lines) is
+    /// indented, and its first non-blank character satisfies `predicate`.
+    fn next_line_starts_with(&self, predicate: impl Fn(char) -> bool) -> bool {
+        let rest = self.rest();
+        let Some(line_end) = rest.find('\n') else {
+            return false;
+        };
+        for line in rest[line_end + 1..].split('\n') {
+            if is_blank(line) {
+                continue;
+            }
+            return line.starts_with(' ') && line.trim_start().starts_with(&predicate);
+        }
+        false
+    }
+
+    fn copy_blank_inline(&mut self) {
+        let len = self.rest().len() - self.rest().trim_start_matches(' ').len();
+        self.copy(len);
+    }
+
+    fn copy_line_end(&mut self) {
+        if self.peek() == Some('\n') {
+            self.copy(1);
+        }
+    }
+
+    /// Copy the next `len` bytes of the input to the output.
+    fn copy(&mut self, len: usize) {
+        self.output.push_str(&self.input[self.pos..self.pos + len]);
+        self.pos += len;
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    /// The rest of the current line, without its line break.
+    fn rest_of_line(&self) -> &str {
+        let rest = self.rest();
+        &rest[..rest.find('\n').unwrap_or(rest.len())]
+    }
+
+    fn error(&self, message: &str) -> PigLatinError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> PigLatinError {
+        PigLatinError::Syntax {
+            line: self.input[..pos].matches('\n').count() + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Return `true` if `line` consists of spaces only (and maybe a carriage return).
+fn is_blank(line: &str) -> bool {
+    line.trim_end_matches(['\n', '\r'])
+        .trim_start_matches(' ')
+        .is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(resource: &str) -> Result<String, PigLatinError> {
+        translate_fluent(resource, &mut Translator::default())
+    }
+
+    #[test]
+    fn comments_and_blank_lines() {
+        let resource = "### Resource comment\n\n## Group comment\n# Message comment\nhello = Hello\n\n\nbye = Bye\n";
+        assert_eq!(
+            translate(resource).unwrap(),
+            "### Resource comment\n\n## Group comment\n# Message comment\nhello = Ellohay\n\n\nbye = Ebyay\n"
+        );
+    }
+
+    #[test]
+    fn multi_line_patterns() {
+        let resource = "intro =\n    Pigs are smart.\n\n    They like mud.\nnext = Oink\n";
+        assert_eq!(
+            translate(resource).unwrap(),
+            "intro =\n    Igspay arehay artsmay.\n\n    Eythay ikelay udmay.\nnext = Oinkhay\n"
+        );
+    }
+
+    #[test]
+    fn attributes_without_value() {
+        let resource =
+            "login-input =\n    .placeholder = Your email\n    .aria-label = Login input\n";
+        assert_eq!(
+            translate(resource).unwrap(),
+            "login-input =\n    .placeholder = Ouryay emailhay\n    .aria-label = Oginlay inputhay\n"
+        );
+    }
+
+    #[test]
+    fn placeables_kept() {
+        let resource = "a = Shared by { $user } on { DATETIME($date, month: \"long\") } { \"{\" }\nb = See { -brand(case: \"short\") } and { { $nested } }\n";
+        assert_eq!(
+            translate(resource).unwrap(),
+            "a = Aredshay byay { $user } onhay { DATETIME($date, month: \"long\") } { \"{\" }\nb = Eesay { -brand(case: \"short\") } andhay { { $nested } }\n"
+        );
+    }
+
+    #[test]
+    fn select_expressions() {
+        let resource = "-brand = { $case ->\n   *[nominative] Firefox\n    [locative] in Firefox\n  }\n    .gender = masculine\npigs =\n    Found { NUMBER($n) ->\n        [0] no pigs\n        [one] one pig,\n            or more\n       *[other] { $n } pigs\n    } today.\n";
+        assert_eq!(
+            translate(resource).unwrap(),
+            "-brand = { $case ->\n   *[nominative] Irefoxfay\n    [locative] inhay Irefoxfay\n  }\n    .gender = masculine\npigs =\n    Oundfay { NUMBER($n) ->\n        [0] onay igspay\n        [one] onehay igpay,\n            orhay oremay\n       *[other] { $n } igspay\n    } odaytay.\n"
+        );
+    }
+
+    #[test]
+    fn crlf() {
+        let resource = "hello = Hello\r\n    world\r\nbye = Bye\r\n";
+        assert_eq!(
+            translate(resource).unwrap(),
+            "hello = Ellohay\r\n    orldway\r\nbye = Ebyay\r\n"
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        for (resource, line) in [
+            ("hello Hello\n", 1),
+            ("hello = Hello\n    .title Hello\n", 2),
+            ("  indented = Hello\n", 1),
+            ("ok = Ok\nhello = { $name\n", 2),
+            ("hello = a } b\n", 1),
+            ("hello = { $n ->\n    one] One\n}\n", 2),
+            ("hello = { \"unterminated }\n", 1),
+            ("1hello = Hello\n", 1),
+        ] {
+            match translate(resource) {
+                Err(PigLatinError::Syntax {
+                    line: error_line, ..
+                }) => assert_eq!(error_line, line, "{resource:?}"),
+                other => panic!("expected a syntax error for {resource:?}, got {other:?}"),
+            }
+        }
+    }
+}
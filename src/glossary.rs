@@ -0,0 +1,154 @@
+//! Glossaries of words with a fixed translation, such as product names.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// # A list of words that are not translated by the rules.
+///
+/// Each word in a glossary is either kept as-is (e.g. product names, acronyms, and
+/// proper nouns), or translated into a fixed override. Words are matched ignoring case,
+/// and overrides take on the casing of the word they replace (see
+/// [`apply_casing_like`](crate::apply_casing_like)). A possessive "'s" is kept, as for
+/// other words.
+///
+/// Use a glossary with [`Translator::with_glossary`](crate::Translator::with_glossary).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{Glossary, Translator};
+/// let glossary = Glossary::new().keep("Rust").translate_as("pig", "oink");
+/// let mut translator = Translator::default().with_glossary(glossary);
+/// assert_eq!(
+///     translator.translate("Rust's pig likes PIGS, not RUST."),
+///     "Rust's oink ikeslay IGSPAY, otnay RUST."
+/// );
+/// ```
+///
+/// Glossaries can also be read from a file with one entry per line: either a word
+/// to keep, or a word and its override, separated by "=". Empty lines, and lines
+/// starting with `#`, are ignored:
+/// ```rust
+/// # use pig_latin::Glossary;
+/// let glossary = Glossary::from_reader("# brands\nRust\npig = oink\n".as_bytes()).unwrap();
+/// assert_eq!(glossary.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Glossary {
+    /// Lower case words, and their override (or `None` to keep them).
+    entries: HashMap<String, Option<String>>,
+}
+
+impl Glossary {
+    /// Create an empty glossary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `word` as it is, in any casing.
+    pub fn keep(mut self, word: &str) -> Self {
+        self.entries.insert(word.to_lowercase(), None);
+        self
+    }
+
+    /// Translate `word` into `translation`, cased like the word it replaces.
+    pub fn translate_as(mut self, word: &str, translation: impl Into<String>) -> Self {
+        self.entries
+            .insert(word.to_lowercase(), Some(translation.into()));
+        self
+    }
+
+    /// Read a glossary from `reader`.
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if an entry has no word,
+    /// or a word containing whitespace.
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let mut glossary = Glossary::new();
+        for (line_idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, translation) = match line.split_once('=') {
+                Some((word, translation)) => (word.trim_end(), Some(translation.trim_start())),
+                None => (line, None),
+            };
+            if word.is_empty() || word.contains(char::is_whitespace) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("expected a single word on line {}", line_idx + 1),
+                ));
+            }
+            glossary = match translation {
+                Some(translation) => glossary.translate_as(word, translation),
+                None => glossary.keep(word),
+            };
+        }
+        Ok(glossary)
+    }
+
+    /// Read a glossary from the file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// The number of words in the glossary.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if the glossary contains no words.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up `word` (in any casing).
+    ///
+    /// Returns `None` if the word is not in the glossary, `Some(None)` if it is kept
+    /// as-is, and `Some(Some(translation))` if it has an override.
+    pub(crate) fn get(&self, word: &str) -> Option<Option<&str>> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.entries
+            .get(&word.to_lowercase())
+            .map(|translation| translation.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_in_code() {
+        let glossary = Glossary::new().keep("iPhone").translate_as("Pig", "oink");
+        assert_eq!(glossary.len(), 2);
+        assert_eq!(glossary.get("IPHONE"), Some(None));
+        assert_eq!(glossary.get("pig"), Some(Some("oink")));
+        assert_eq!(glossary.get("pigs"), None);
+    }
+
+    #[test]
+    fn parse_glossary() {
+        let glossary = Glossary::from_reader(
+            "# comment\n\n  iPhone \npig=oink\nhello = ello there\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(glossary.len(), 3);
+        assert_eq!(glossary.get("iphone"), Some(None));
+        assert_eq!(glossary.get("Pig"), Some(Some("oink")));
+        assert_eq!(glossary.get("hello"), Some(Some("ello there")));
+    }
+
+    #[test]
+    fn invalid_entries() {
+        for text in ["= oink\n", "guinea pig = oink\n"] {
+            let error = Glossary::from_reader(text.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Translating HTML documents.
+
+use crate::markup::{translate_keeping_references, Node, Scanner, Tag};
+use crate::{PigLatinError, Translator};
+
+/// The attributes translated by default by [`translate_html`]: those that are
+/// displayed to, or read out for, users.
+pub const DEFAULT_HTML_ATTRIBUTES: &[&str] = &["title", "alt", "placeholder", "aria-label"];
+
+/// The elements whose content is not translated.
+const UNTRANSLATED_ELEMENTS: &[&str] = &["script", "style", "code", "pre"];
+
+/// The elements without content, and thus without end tags.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// The elements whose content is not markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// # Translate the text of an HTML document (or fragment) into Pig-Latin.
+///
+/// Translates the text between tags, and the values of the `attributes` (such as
+/// [`DEFAULT_HTML_ATTRIBUTES`]). Not translated is the content of `<script>`, `<style>`,
+/// `<code>` and `<pre>` elements, and of elements marked `translate="no"`.
+///
+/// Everything else is kept byte for byte: tags, other attributes, comments, and the
+/// layout of the document. Character references (such as `&nbsp;` or `&amp;`) are kept
+/// as they are, but count as the characters they stand for, so that "caf&eacute;" and
+/// "don&rsquo;t" are translated as one word. Unknown references are plain text.
+///
+/// Returns [`PigLatinError::Syntax`] if a tag or comment is not terminated.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_html, Translator, DEFAULT_HTML_ATTRIBUTES};
+/// let html = r#"<div class="intro" title="Welcome">
+///   <p>Hello&nbsp;there, <img src="pig.png" alt="A pig"> friend!</p>
+///   <pre>fn main() {}</pre>
+/// </div>"#;
+/// assert_eq!(
+///     translate_html(html, &mut Translator::default(), DEFAULT_HTML_ATTRIBUTES).unwrap(),
+///     r#"<div class="intro" title="Elcomeway">
+///   <p>Ellohay&nbsp;erethay, <img src="pig.png" alt="Ahay igpay"> iendfray!</p>
+///   <pre>fn main() {}</pre>
+/// </div>"#
+/// );
+/// ```
+pub fn translate_html(
+    html: &str,
+    translator: &mut Translator,
+    attributes: &[&str],
+) -> Result<String, PigLatinError> {
+    let mut translated = String::with_capacity(html.len() * 2);
+    // the outermost untranslated element, and how many elements of the same name it contains
+    let mut untranslated: Option<(&str, usize)> = None;
+    let mut scanner = Scanner::new(html);
+    while let Some(node) = scanner.next() {
+        match node? {
+            Node::Text(text) if untranslated.is_none() => {
+                translate_text(text, translator, &mut translated);
+            }
+            Node::StartTag(tag) => {
+                match &mut untranslated {
+                    Some((name, nested)) => {
+                        translated.push_str(tag.raw);
+                        if tag.name.eq_ignore_ascii_case(name) && !tag.self_closing {
+                            *nested += 1;
+                        }
+                    }
+                    None => {
+                        let translate = tag
+                            .attribute("translate")
+                            .is_none_or(|translate| !translate.eq_ignore_ascii_case("no"));
+                        if translate {
+                            translate_tag(&tag, translator, attributes, &mut translated);
+                        } else {
+                            translated.push_str(tag.raw);
+                        }
+                        let skip = !translate || is_one_of(tag.name, UNTRANSLATED_ELEMENTS);
+                        if skip && !tag.self_closing && !is_one_of(tag.name, VOID_ELEMENTS) {
+                            untranslated = Some((tag.name, 0));
+                        }
+                    }
+                }
+                if is_one_of(tag.name, RAW_TEXT_ELEMENTS) && !tag.self_closing {
+                    translated.push_str(scanner.raw_text(tag.name));
+                }
+            }
+            Node::EndTag(tag) => {
+                if let Some((name, nested)) = &mut untranslated {
+                    if tag.name.eq_ignore_ascii_case(name) {
+                        match nested {
+                            0 => untranslated = None,
+                            _ => *nested -= 1,
+                        }
+                    }
+                }
+                translated.push_str(tag.raw);
+            }
+            Node::Text(raw) | Node::Other(raw) => translated.push_str(raw),
+        }
+    }
+    Ok(translated)
+}
+
+/// Push `tag`, translating the values of its `attributes`.
+fn translate_tag(tag: &Tag, translator: &mut Translator, attributes: &[&str], output: &mut String) {
+    let mut copied = 0;
+    for attribute in tag.attributes() {
+        let Some(value) = attribute.value else {
+            continue;
+        };
+        if !is_one_of(attribute.name, attributes) {
+            continue;
+        }
+        output.push_str(&tag.raw[copied..attribute.value_start]);
+        translate_text(value, translator, output);
+        copied = attribute.value_start + value.len();
+    }
+    output.push_str(&tag.raw[copied..]);
+}
+
+/// Translate `text`, keeping character references as they are.
+pub(crate) fn translate_text(text: &str, translator: &mut Translator, output: &mut String) {
+    translate_keeping_references(text, output, |piece, output| {
+        translator
+            .translate_into(piece, output)
+            .expect("writing to a String should not fail");
+    });
+}
+
+fn is_one_of(name: &str, names: &[&str]) -> bool {
+    names
+        .iter()
+        .any(|candidate| candidate.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(html: &str, attributes: &[&str]) -> String {
+        translate_html(html, &mut Translator::default(), attributes).unwrap()
+    }
+
+    #[test]
+    fn untranslated_elements() {
+        let html = "<P>Pig <code>let pig = 1;</code> <pre>a <b>pig</b> <pre>b</pre> c</pre> end<br/></P>\n<SCRIPT>if (a<b) { say(\"</p>\"); }</SCRIPT><style>p > b { color: pink }</style>";
+        assert_eq!(
+            translate(html, DEFAULT_HTML_ATTRIBUTES),
+            "<P>Igpay <code>let pig = 1;</code> <pre>a <b>pig</b> <pre>b</pre> c</pre> endhay<br/></P>\n<SCRIPT>if (a<b) { say(\"</p>\"); }</SCRIPT><style>p > b { color: pink }</style>"
+        );
+    }
+
+    #[test]
+    fn translate_no() {
+        let html =
+            "<p>Pig <span translate=\"no\" title=\"Pig\">Pig <span>Farm</span> Pig</span> pig</p>";
+        assert_eq!(
+            translate(html, DEFAULT_HTML_ATTRIBUTES),
+            "<p>Igpay <span translate=\"no\" title=\"Pig\">Pig <span>Farm</span> Pig</span> igpay</p>"
+        );
+    }
+
+    #[test]
+    fn translate_no_on_void_element() {
+        let html = "<p>A <img translate=\"no\" alt=\"pig\"> pig</p><p>more pigs</p>";
+        assert_eq!(
+            translate(html, DEFAULT_HTML_ATTRIBUTES),
+            "<p>Ahay <img translate=\"no\" alt=\"pig\"> igpay</p><p>oremay igspay</p>"
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        let html = "<input type=text placeholder='Your name' data-x=\"Pig\" aria-label=Name value=\"Pig &amp; co\" disabled>";
+        assert_eq!(
+            translate(html, DEFAULT_HTML_ATTRIBUTES),
+            "<input type=text placeholder='Ouryay amenay' data-x=\"Pig\" aria-label=Amenay value=\"Pig &amp; co\" disabled>"
+        );
+        assert_eq!(
+            translate(html, &["VALUE"]),
+            "<input type=text placeholder='Your name' data-x=\"Pig\" aria-label=Name value=\"Igpay &amp; ocay\" disabled>"
+        );
+    }
+
+    #[test]
+    fn entities_and_comments() {
+        let html = "<!DOCTYPE html>\n<!-- a pig -->Fish&amp;chips &#8212; &bogus done &lt;3";
+        assert_eq!(
+            translate(html, &[]),
+            "<!DOCTYPE html>\n<!-- a pig -->Ishfay&amp;ipschay &#8212; &ogusbay oneday &lt;3"
+        );
+        assert_eq!(
+            translate("Don&rsquo;t &amp; won&#39;t, pig&apos;s", &[]),
+            "On&rsquo;tday &amp; on&#39;tway, igpay&apos;s"
+        );
+        assert_eq!(translate("Don&rsquo;t won’t", &[]), "On&rsquo;tday on’tway");
+        assert_eq!(
+            translate("Caf&eacute; &bogus; &amp", &[]),
+            "Af&eacute;cay &ogusbay; &amphay"
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        assert!(matches!(
+            translate_html("<p>\n<a href=\"x\"", &mut Translator::default(), &[]),
+            Err(PigLatinError::Syntax { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,626 @@
+//! Translating JSON documents, such as the locale files of i18next or react-intl.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::{PigLatinError, Translator};
+
+/// # Translate the string values of a JSON document into Pig-Latin.
+///
+/// Keys, numbers, booleans and `null` are kept as they are, as is the formatting of
+/// the document (whitespace, and the order of keys). Strings are copied byte for byte
+/// unless their translation differs. Translated strings reuse the escape sequences of
+/// the original for the characters they stand for (so "\u00e9t\u00e9" becomes
+/// "\u00e9t\u00e9hay"), and otherwise only escape quotes, backslashes and control
+/// characters.
+///
+/// If `keys` is given, only the values whose key path matches this glob are translated.
+/// The key path of a value consists of the keys of the objects (and the indices of the
+/// arrays) containing it, joined by ".", e.g. "menu.items.0.label". In the glob,
+/// `*` matches any part of a single key, and `**` any number of keys (including none),
+/// e.g. "menu.*.label" or "**.title".
+///
+/// Format placeholders in strings are only kept intact if `translator` protects them,
+/// see [`Protection::with_placeholders`](crate::Protection::with_placeholders).
+///
+/// Returns [`PigLatinError::Syntax`] if `document` is not well-formed JSON.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_json, Protection, Translator};
+/// let document = r#"{
+///   "title": "Hello, {{name}}!",
+///   "count": 3,
+///   "menu": { "open": "Open file", "id": "file-open" }
+/// }"#;
+/// let mut translator =
+///     Translator::default().with_protection(Protection::default().with_placeholders());
+/// assert_eq!(
+///     translate_json(document, &mut translator, None).unwrap(),
+///     r#"{
+///   "title": "Ellohay, {{name}}!",
+///   "count": 3,
+///   "menu": { "open": "Openhay ilefay", "id": "ilefay-openhay" }
+/// }"#
+/// );
+/// assert_eq!(
+///     translate_json(document, &mut translator, Some("menu.open")).unwrap(),
+///     r#"{
+///   "title": "Hello, {{name}}!",
+///   "count": 3,
+///   "menu": { "open": "Openhay ilefay", "id": "file-open" }
+/// }"#
+/// );
+/// ```
+pub fn translate_json(
+    document: &str,
+    translator: &mut Translator,
+    keys: Option<&str>,
+) -> Result<String, PigLatinError> {
+    match keys {
+        Some(glob) => translate_json_values(document, translator, &|path| {
+            glob_matches(glob.as_bytes(), path.join(".").as_bytes())
+        }),
+        None => translate_json_values(document, translator, &|_| true),
+    }
+}
+
+/// Implement [`translate_json`], translating the string values for whose key path
+/// `translates` returns `true`.
+pub(crate) fn translate_json_values(
+    document: &str,
+    translator: &mut Translator,
+    translates: &dyn Fn(&[String]) -> bool,
+) -> Result<String, PigLatinError> {
+    let mut parser = Parser::new(document, Some(translator), translates);
+    parser.document()?;
+    Ok(parser.output)
+}
+
+/// The byte ranges of the values of a JSON document, by their key paths.
+pub(crate) type ValueRanges = HashMap<Vec<String>, Range<usize>>;
+
+/// Parse `document`, returning the byte ranges of all its values.
+pub(crate) fn value_ranges(document: &str) -> Result<ValueRanges, PigLatinError> {
+    let mut parser = Parser::new(document, None, &|_| false);
+    parser.ranges = Some(HashMap::new());
+    parser.document()?;
+    Ok(parser.ranges.unwrap_or_default())
+}
+
+/// Return the value of the JSON string `string` (with its quotes), if well-formed.
+pub(crate) fn unquote(string: &str) -> Option<String> {
+    let mut parser = Parser::new(string, None, &|_| false);
+    let (value, _) = parser.string().ok()?;
+    (parser.pos == string.len()).then_some(value)
+}
+
+/// The whitespace around the members of a JSON object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ObjectLayout {
+    /// The whitespace before each member, e.g. "\n    ".
+    pub(crate) indent: String,
+    /// The text between a key and its value, e.g. " : ".
+    pub(crate) colon: String,
+    /// The whitespace before the closing "}".
+    pub(crate) close: String,
+}
+
+impl ObjectLayout {
+    /// Return the layout of the well-formed object `object`, or `None` if it has no
+    /// members.
+    pub(crate) fn of(object: &str) -> Option<Self> {
+        let members = &object[1..object.len() - 1];
+        let key = members.trim_start_matches(WHITESPACE);
+        let mut chars = key.char_indices().skip(1);
+        let key_len = loop {
+            match chars.next()? {
+                (_, '\\') => {
+                    chars.next();
+                }
+                (idx, '"') => break idx + 1,
+                _ => {}
+            }
+        };
+        let after_key = &key[key_len..];
+        let value = after_key.trim_start_matches(WHITESPACE)[1..].trim_start_matches(WHITESPACE);
+        Some(ObjectLayout {
+            indent: members[..members.len() - key.len()].to_string(),
+            colon: after_key[..after_key.len() - value.len()].to_string(),
+            close: members[members.trim_end_matches(WHITESPACE).len()..].to_string(),
+        })
+    }
+
+    /// The layout of an object that is the value of a member of this one.
+    pub(crate) fn nested(&self) -> Self {
+        let step = self.indent.strip_prefix(&self.close).unwrap_or_default();
+        ObjectLayout {
+            indent: format!("{}{step}", self.indent),
+            colon: self.colon.clone(),
+            close: self.indent.clone(),
+        }
+    }
+
+    /// Push the member `"key": value` (with `value` given as JSON), without any
+    /// separator before it.
+    pub(crate) fn push_member(&self, output: &mut String, key: &str, value: &str) {
+        output.push_str(&self.indent);
+        push_quoted(output, key, &[]);
+        output.push_str(&self.colon);
+        output.push_str(value);
+    }
+
+    /// Return an object with `members`, given as keys and JSON values.
+    pub(crate) fn object(&self, members: &[(&str, &str)]) -> String {
+        let mut object = String::from("{");
+        for (idx, &(key, value)) in members.iter().enumerate() {
+            if idx > 0 {
+                object.push(',');
+            }
+            self.push_member(&mut object, key, value);
+        }
+        object.push_str(&self.close);
+        object.push('}');
+        object
+    }
+}
+
+/// The whitespace allowed between the tokens of JSON.
+const WHITESPACE: [char; 4] = [' ', '\t', '\n', '\r'];
+
+/// The escape sequences in a string, together with the characters they stand for.
+type Escapes<'a> = Vec<(char, &'a str)>;
+
+/// A parser of JSON documents, writing their translation to `output` as it goes.
+struct Parser<'a, 't, 'k> {
+    input: &'a str,
+    /// The byte index of the next character to parse.
+    pos: usize,
+    output: String,
+    /// The translator, or `None` to only parse the document.
+    translator: Option<&'t mut Translator>,
+    /// Whether to translate the string value at a key path.
+    translates: &'k dyn Fn(&[String]) -> bool,
+    /// The keys of the value being parsed.
+    path: Vec<String>,
+    /// The byte ranges of the values parsed so far, if they are recorded.
+    ranges: Option<ValueRanges>,
+}
+
+impl<'a, 't, 'k> Parser<'a, 't, 'k> {
+    fn new(
+        input: &'a str,
+        translator: Option<&'t mut Translator>,
+        translates: &'k dyn Fn(&[String]) -> bool,
+    ) -> Self {
+        Parser {
+            input,
+            pos: 0,
+            output: String::with_capacity(input.len() * 2),
+            translator,
+            translates,
+            path: Vec::new(),
+            ranges: None,
+        }
+    }
+
+    /// Parse the whole input, a single value.
+    fn document(&mut self) -> Result<(), PigLatinError> {
+        self.copy_whitespace();
+        self.value()?;
+        self.copy_whitespace();
+        if self.pos < self.input.len() {
+            return Err(self.error("unexpected text after the document"));
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<(), PigLatinError> {
+        let start = self.pos;
+        match self.peek() {
+            Some('{') => self.object()?,
+            Some('[') => self.array()?,
+            Some('"') => {
+                let (value, escapes) = self.string()?;
+                let translated = match &mut self.translator {
+                    Some(translator) if (self.translates)(&self.path) => {
+                        Some(translator.translate(&value))
+                    }
+                    _ => None,
+                };
+                match translated {
+                    Some(translated) if translated != value => {
+                        push_quoted(&mut self.output, &translated, &escapes);
+                    }
+                    _ => self.output.push_str(&self.input[start..self.pos]),
+                }
+            }
+            Some(_) => self.literal()?,
+            None => return Err(self.error("expected a value")),
+        }
+        if let Some(ranges) = &mut self.ranges {
+            ranges.insert(self.path.clone(), start..self.pos);
+        }
+        Ok(())
+    }
+
+    fn object(&mut self) -> Result<(), PigLatinError> {
+        self.copy(1);
+        self.copy_whitespace();
+        if self.peek() == Some('}') {
+            self.copy(1);
+            return Ok(());
+        }
+        loop {
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a key"));
+            }
+            let start = self.pos;
+            let (key, _) = self.string()?;
+            self.output.push_str(&self.input[start..self.pos]);
+            self.copy_whitespace();
+            self.expect(':')?;
+            self.copy_whitespace();
+            self.path.push(key);
+            self.value()?;
+            self.path.pop();
+            self.copy_whitespace();
+            if self.end_of_sequence('}')? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<(), PigLatinError> {
+        self.copy(1);
+        self.copy_whitespace();
+        if self.peek() == Some(']') {
+            self.copy(1);
+            return Ok(());
+        }
+        for idx in 0.. {
+            self.path.push(idx.to_string());
+            self.value()?;
+            self.path.pop();
+            self.copy_whitespace();
+            if self.end_of_sequence(']')? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the "," between the members of an object or array, returning `false`,
+    /// or the `close`ing bracket, returning `true`.
+    fn end_of_sequence(&mut self, close: char) -> Result<bool, PigLatinError> {
+        match self.peek() {
+            Some(',') => {
+                self.copy(1);
+                self.copy_whitespace();
+                Ok(false)
+            }
+            Some(c) if c == close => {
+                self.copy(1);
+                Ok(true)
+            }
+            _ => Err(self.error(&format!("expected \",\" or {close:?}"))),
+        }
+    }
+
+    /// Parse a string (without copying it), returning its unescaped value, and the
+    /// escape sequences in it.
+    fn string(&mut self) -> Result<(String, Escapes<'a>), PigLatinError> {
+        let start = self.pos;
+        let mut value = String::new();
+        let mut escapes = Vec::new();
+        let content = &self.input[start + 1..];
+        let mut chars = content.char_indices();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos = start + 1 + idx + 1;
+                    return Ok((value, escapes));
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let unit = hex4(&mut chars);
+                            let code_point = match unit {
+                                Some(high @ 0xD800..=0xDBFF) => {
+                                    let low = match (chars.next(), chars.next()) {
+                                        (Some((_, '\\')), Some((_, 'u'))) => hex4(&mut chars),
+                                        _ => None,
+                                    };
+                                    low.filter(|low| (0xDC00..=0xDFFF).contains(low))
+                                        .map(|low| {
+                                            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                                        })
+                                }
+                                unit => unit,
+                            };
+                            match code_point.and_then(char::from_u32) {
+                                Some(c) => c,
+                                None => {
+                                    self.pos = start;
+                                    return Err(self.error("malformed unicode escape"));
+                                }
+                            }
+                        }
+                        _ => {
+                            self.pos = start;
+                            return Err(self.error("malformed escape sequence"));
+                        }
+                    };
+                    value.push(escaped);
+                    escapes.push((escaped, &content[idx..chars.offset()]));
+                }
+                '\n' => break,
+                c => value.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    /// A number, `true`, `false` or `null`.
+    fn literal(&mut self) -> Result<(), PigLatinError> {
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')))
+            .unwrap_or(self.rest().len());
+        let literal = &self.rest()[..len];
+        let is_number = number_len(literal.as_bytes()) == Some(len);
+        if !(is_number || matches!(literal, "true" | "false" | "null")) {
+            return Err(self.error("expected a value"));
+        }
+        self.copy(len);
+        Ok(())
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PigLatinError> {
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("expected {c:?}")));
+        }
+        self.copy(c.len_utf8());
+        Ok(())
+    }
+
+    fn copy_whitespace(&mut self) {
+        let rest = self.rest();
+        let len = rest.len() - rest.trim_start_matches(WHITESPACE).len();
+        self.copy(len);
+    }
+
+    /// Copy the next `len` bytes of the input to the output.
+    fn copy(&mut self, len: usize) {
+        self.output.push_str(&self.input[self.pos..self.pos + len]);
+        self.pos += len;
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> PigLatinError {
+        PigLatinError::Syntax {
+            line: self.input[..self.pos].matches('\n').count() + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Parse the four hex digits of a unicode escape.
+fn hex4(chars: &mut impl Iterator<Item = (usize, char)>) -> Option<u32> {
+    (0..4).try_fold(0, |code, _| Some(code * 16 + chars.next()?.1.to_digit(16)?))
+}
+
+/// Return the length of the JSON number `text` starts with, if any.
+fn number_len(text: &[u8]) -> Option<usize> {
+    let digits = |from: usize| {
+        text[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut len = usize::from(text.first() == Some(&b'-'));
+    match text.get(len) {
+        Some(b'0') => len += 1,
+        Some(b'1'..=b'9') => len += digits(len),
+        _ => return None,
+    }
+    if text.get(len) == Some(&b'.') {
+        match digits(len + 1) {
+            0 => return None,
+            n_digits => len += 1 + n_digits,
+        }
+    }
+    if matches!(text.get(len), Some(b'e' | b'E')) {
+        len += 1;
+        if matches!(text.get(len), Some(b'+' | b'-')) {
+            len += 1;
+        }
+        match digits(len) {
+            0 => return None,
+            n_digits => len += n_digits,
+        }
+    }
+    Some(len)
+}
+
+/// Push `value` as a JSON string, spelling characters like the given `escapes`.
+pub(crate) fn push_quoted(output: &mut String, value: &str, escapes: &[(char, &str)]) {
+    output.push('"');
+    for c in value.chars() {
+        if let Some((_, escape)) = escapes.iter().find(|&&(escaped, _)| escaped == c) {
+            output.push_str(escape);
+            continue;
+        }
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                write!(output, "\\u{:04x}", c as u32).expect("writing to a String should not fail")
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+/// Match `path` against `glob`, where `*` matches anything but ".", and `**` anything.
+/// A `**` between dots (or at either end of `glob`) also matches no keys at all, so
+/// that "**.title" matches "title", and "menu.**" matches "menu".
+fn glob_matches(glob: &[u8], path: &[u8]) -> bool {
+    match glob {
+        [] => path.is_empty(),
+        [b'*', b'*', b'.', rest @ ..] => {
+            glob_matches(rest, path)
+                || (0..path.len())
+                    .any(|idx| path[idx] == b'.' && glob_matches(rest, &path[idx + 1..]))
+        }
+        [b'.', b'*', b'*'] => path.is_empty() || path[0] == b'.',
+        [b'*', b'*', rest @ ..] => (0..=path.len()).any(|idx| glob_matches(rest, &path[idx..])),
+        [b'*', rest @ ..] => (0..=path.len())
+            .take_while(|&idx| idx == 0 || path[idx - 1] != b'.')
+            .any(|idx| glob_matches(rest, &path[idx..])),
+        [byte, rest @ ..] => path.first() == Some(byte) && glob_matches(rest, &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Protection;
+
+    fn translate(document: &str, keys: Option<&str>) -> Result<String, PigLatinError> {
+        let protection = Protection::default().with_placeholders();
+        let mut translator = Translator::default().with_protection(protection);
+        translate_json(document, &mut translator, keys)
+    }
+
+    #[test]
+    fn formatting_kept() {
+        let document = "\t{\"b\":[\"pig\" , 1.5e3,true,null,{}, []],\r\n\n  \"a\" : \"oink\"\n}\n";
+        assert_eq!(
+            translate(document, None).unwrap(),
+            "\t{\"b\":[\"igpay\" , 1.5e3,true,null,{}, []],\r\n\n  \"a\" : \"oinkhay\"\n}\n"
+        );
+        assert_eq!(translate("\"pig\"", None).unwrap(), "\"igpay\"");
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            translate(
+                r#"{"say\n": "Say \"oink\"\n\u00e9t\u00E9 \ud83d\udc37\/pig"}"#,
+                None
+            )
+            .unwrap(),
+            r#"{"say\n": "Aysay \"oinkhay\"\n\u00e9t\u00e9hay \ud83d\udc37\/igpay"}"#
+        );
+        assert_eq!(
+            translate(r#"["\u002e\/\u002E", "caf\u00e9\t"]"#, None).unwrap(),
+            r#"["\u002e\/\u002E", "af\u00e9cay\t"]"#
+        );
+    }
+
+    #[test]
+    fn placeholders_kept() {
+        let document =
+            r#"{"a": "{{count}} pigs", "b": "{count, plural, one {# pig} other {# pigs}}"}"#;
+        assert_eq!(
+            translate(document, None).unwrap(),
+            r#"{"a": "{{count}} igspay", "b": "{count, plural, one {# igpay} other {# igspay}}"}"#
+        );
+    }
+
+    #[test]
+    fn key_globs() {
+        let document = r#"{"menu": {"file": {"label": "File", "id": "file"}, "items": ["Open", "Save"]}, "label": "Pig"}"#;
+        for (keys, expected) in [
+            (
+                "menu.*.label",
+                r#"{"menu": {"file": {"label": "Ilefay", "id": "file"}, "items": ["Open", "Save"]}, "label": "Pig"}"#,
+            ),
+            (
+                "**label",
+                r#"{"menu": {"file": {"label": "Ilefay", "id": "file"}, "items": ["Open", "Save"]}, "label": "Igpay"}"#,
+            ),
+            (
+                "**.label",
+                r#"{"menu": {"file": {"label": "Ilefay", "id": "file"}, "items": ["Open", "Save"]}, "label": "Igpay"}"#,
+            ),
+            (
+                "menu.items.1",
+                r#"{"menu": {"file": {"label": "File", "id": "file"}, "items": ["Open", "Avesay"]}, "label": "Pig"}"#,
+            ),
+            (
+                "*",
+                r#"{"menu": {"file": {"label": "File", "id": "file"}, "items": ["Open", "Save"]}, "label": "Igpay"}"#,
+            ),
+        ] {
+            assert_eq!(translate(document, Some(keys)).unwrap(), expected, "{keys}");
+        }
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_matches(b"a.*.c", b"a.b.c"));
+        assert!(!glob_matches(b"a.*.c", b"a.b.b.c"));
+        assert!(glob_matches(b"a.**.c", b"a.b.b.c"));
+        assert!(glob_matches(b"**", b""));
+        assert!(glob_matches(b"**.title", b"title"));
+        assert!(glob_matches(b"**.title", b"a.b.title"));
+        assert!(!glob_matches(b"**.title", b"subtitle"));
+        assert!(glob_matches(b"a.**.c", b"a.c"));
+        assert!(glob_matches(b"a.**", b"a"));
+        assert!(glob_matches(b"a.**", b"a.b.c"));
+        assert!(!glob_matches(b"a.**", b"ab"));
+        assert!(glob_matches(b"*_title", b"page_title"));
+        assert!(!glob_matches(b"*_title", b"page.sub_title"));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        for (document, line) in [
+            ("", 1),
+            ("{\"a\": \"b\",\n}", 2),
+            ("{\"a\" \"b\"}", 1),
+            ("[1,\n 2\n \"x\"]", 3),
+            ("{\"a\": \"unterminated}\n", 1),
+            ("{\"a\": \"\\x\"}", 1),
+            ("{\"a\": \"\\ud83d\"}", 1),
+            ("[True]", 1),
+            ("{\"x\": -inf}", 1),
+            ("[nan]", 1),
+            ("[01]", 1),
+            ("[1.]", 1),
+            ("[1e+]", 1),
+            ("[+1]", 1),
+            ("{}\n{}", 2),
+        ] {
+            match translate(document, None) {
+                Err(PigLatinError::Syntax {
+                    line: error_line, ..
+                }) => assert_eq!(error_line, line, "{document:?}"),
+                other => panic!("expected a syntax error for {document:?}, got {other:?}"),
+            }
+        }
+    }
+}
@@ -10,7 +10,31 @@
 //! In the special case of single-word inputs,
 //! [`pig_latin::translate_word`](crate::translate_word) may be slightly faster
 //! -- but may provide wrong results on non-single-word inputs, and behavior
-//! on such inputs may change without warning.
+//! on such inputs may change without warning. To check inputs instead,
+//! use [`try_translate_word`], which returns a [`PigLatinError`] for them.
+//!
+//! For translating large amounts of text, such as streams that do not fit into
+//! memory, see [`Translator`].
+//!
+//! To build on the same segmentation of text into words as [`translate`], e.g. for
+//! highlighting, see [`tokens`]. To relate byte ranges of a translation to those of
+//! its input, e.g. for diagnostics, see [`translate_with_source_map`].
+//!
+//! To pseudo-localize the strings of a user interface, keeping format placeholders
+//! intact, see [`PseudoLocalizer`]. To pseudo-localize whole message catalogs, see
+//! [`translate_po`] and [`translate_fluent`], for the string values of JSON documents,
+//! [`translate_json`], and for the string resources of mobile apps,
+//! [`translate_android_strings`], [`translate_apple_strings`], [`translate_stringsdict`]
+//! and [`translate_xcstrings`].
+//!
+//! To translate only the text of HTML, keeping its markup intact, see [`translate_html`],
+//! and for the prose of Markdown, [`translate_markdown`]. To translate subtitles, keeping
+//! their timings intact, see [`translate_srt`] and [`translate_webvtt`].
+//!
+//! To go back from Pig-Latin to English, use
+//! [`pig_latin::untranslate`](crate::untranslate). As this is ambiguous, the
+//! English candidates can be ranked using a [`WordModel`], such as the
+//! [`WordFrequencies`] of a word list.
 //!  
 //! ## One True Dialect
 //!
@@ -27,6 +51,20 @@
 //!    as "part of" the consonant as far as translation is concerned.
 //!     - This is done to preserve pronouncability according to English
 //!       phonetics.
+//!  - Words joined by an apostrophe are one word: the consonants are moved
+//!    in front of the whole contraction ("don't" becomes "on'tday").
+//!     - A possessive "'s" at the end of a word stays at the end, after the
+//!       suffix ("pig's" becomes "igpay's"). As it looks the same, this applies
+//!       to contractions of "is" and "has" too ("it's" becomes "ithay's").
+//!  - Words without any vowel ("rhythm", "psst") are moved as a whole, and get the
+//!    general suffix ("rhythmay", "psstay").
+//!  - Letters with diacritics count as their base letter: "é", "Ü" and "å"
+//!    are as much vowels as "e", "U" and "a" (whether precomposed, or written
+//!    using combining marks).
+//!
+//! Should you, against all advice, have to deal with speakers of lesser dialects,
+//! their rules can be described by a [`Dialect`], and passed to
+//! [`translate_with`] and friends.
 //!
 //! ## Example
 //!
@@ -49,23 +87,28 @@
 //!
 //! ## Binaries/executables
 //!
-//! 1. For a command line interface to the [`translate`] function, see [`pig`](../pig/fn.main.html).
+//! 1. For a command line interface to the [`translate`] function, and to the translators
+//!    of file formats, see [`pig`](../pig/fn.main.html).
 //! 2. For a binary that is useful for profiling this library, see [`lorem_ipsum_profiling`](../lorem_ipsum_profiling/fn.main.html).
 //! 3. For benchmarking the performance of this library, there is a [`criterion`][criterion-url]-based benchmark (See `benches/translation_benchmark.rs`).
 //!    The benchmark can be run using `cargo bench`.
 //!
 //! [criterion-url]: https://bheisler.github.io/criterion.rs/book/index.html
 
-use std::iter::once;
+use std::convert::Infallible;
+use std::{fmt, io};
 
 /// # Translate English into Pig-Latin.
 ///
 /// This function translates arbitrary English text into [OTDoPL](crate#one-true-dialect) Pig-Latin.
 ///
 /// This is done by tokenizing the text into words (contiguous, non-whitespace, non-punctuation
-/// substrings), translating the words (cf. [`translate_word`]), and re-inserting the non-word
-/// characters. Thus, whitespace, layout, structure, and punctuation should be preserved in
-/// translation.
+/// substrings, delimited according to the Unicode word boundary rules), translating the words
+/// (cf. [`translate_word`]), and re-inserting the non-word characters. Thus, whitespace,
+/// layout, structure, and punctuation should be preserved in translation.
+///
+/// Text that is not English, such as URLs, email addresses, file paths, numbers,
+/// version strings and UUIDs, is copied verbatim, see [`Protection`].
 ///
 /// ## Examples
 ///
@@ -88,6 +131,15 @@ use std::iter::once;
 /// assert_eq!(translate("Question:"), String::from("Estionquay:"));
 /// ```
 ///
+/// Keep URLs and numbers as they are:
+/// ```rust
+/// # use pig_latin::translate;
+/// assert_eq!(
+///     translate("Read https://example.com/pig in 5 minutes."),
+///     String::from("Eadray https://example.com/pig inhay 5 inutesmay.")
+/// );
+/// ```
+///
 /// Translate words starting with vowels:
 /// ```rust
 /// # use pig_latin::translate;
@@ -97,18 +149,24 @@ use std::iter::once;
 /// );
 /// ```
 pub fn translate(english: &str) -> String {
-    let substring_ranges_iter = once((0, false))
-        .chain(
-            english
-                .match_indices(|c: char| c.is_ascii_punctuation() || c.is_whitespace())
-                .map(|(match_idx, match_str)| (match_idx, match_idx + match_str.len()))
-                .flat_map(|(match_start, match_end)| {
-                    once((match_start, true)).chain(once((match_end, false)))
-                }),
-        )
-        .chain(once((english.len(), false)));
-    let mut last_match_idx = 0;
-    let mut last_is_punct_or_ws = false;
+    translate_with(english, &OTDOPL)
+}
+
+/// # Translate English into Pig-Latin, in any dialect.
+///
+/// Like [`translate`], but following the rules of `dialect` instead of OTDoPL.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_with, Dialect};
+/// let dialect = Dialect::builder().vowel_suffix("yay").build();
+/// assert_eq!(
+///     translate_with("Hello, I am a pig!", &dialect),
+///     String::from("Ellohay, Iyay amyay ayay igpay!")
+/// );
+/// ```
+pub fn translate_with(english: &str, dialect: &Dialect) -> String {
     // Note on optimization:
     //  - a single initial pass to get a data-based capacity estimate seems to cost more
     //    than the avoidance of re-sizing saves
@@ -116,26 +174,108 @@ pub fn translate(english: &str) -> String {
     //  - providing a lower limit for small strings yields no speed gain
     let capacity = (english.len() as f64 * 1.3).floor() as i64 as usize;
     let mut translated = String::with_capacity(capacity);
-    for (match_idx, is_punct_or_ws) in substring_ranges_iter {
-        let from = last_match_idx;
-        let to = match_idx;
-        let from_is_punct_or_ws = last_is_punct_or_ws;
-        last_match_idx = match_idx;
-        last_is_punct_or_ws = is_punct_or_ws;
-        if !(to > from) {
-            continue;
-        }
-        if !from_is_punct_or_ws {
-            translate_word_inplace(&english[from..to], &mut translated);
-        } else {
-            translated.push_str(&english[from..to]);
-        }
-    }
+    let mut buffers = WordBuffers::default();
+    translate_text(
+        english,
+        dialect,
+        &DEFAULT_PROTECTION,
+        None,
+        &mut buffers,
+        |piece| {
+            translated.push_str(piece);
+            Ok::<(), Infallible>(())
+        },
+    )
+    .unwrap_or_else(|never| match never {});
     translated
 }
 
-use crate::details::translate_word_inplace;
-pub use crate::details::{apply_casing_like, translate_word};
+/// # Translate English into Pig-Latin, writing into a sink.
+///
+/// Like [`translate`], but instead of allocating a new `String`, the translation
+/// is written piece by piece into `sink`. Any error from `sink` is returned.
+/// To also re-use the scratch buffers between calls, see [`Translator::translate_into`].
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::translate_into;
+/// let mut translated = String::from("Pig-Latin: ");
+/// translate_into("Hello world!", &mut translated).unwrap();
+/// assert_eq!(translated, "Pig-Latin: Ellohay orldway!");
+/// ```
+pub fn translate_into(english: &str, sink: &mut impl fmt::Write) -> fmt::Result {
+    Translator::default().translate_into(english, sink)
+}
+
+/// # Translate English into Pig-Latin, writing into an [`io::Write`].
+///
+/// Like [`translate_into`], but for byte-oriented sinks, such as files or stdout.
+/// The translation is written as UTF-8. Any error from `writer` is returned.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::translate_to_io;
+/// let mut translated = Vec::new();
+/// translate_to_io("Hello world!", &mut translated).unwrap();
+/// assert_eq!(translated, b"Ellohay orldway!");
+/// ```
+pub fn translate_to_io(english: &str, writer: &mut impl io::Write) -> io::Result<()> {
+    Translator::default().translate_to_io(english, writer)
+}
+
+pub use crate::android::translate_android_strings;
+pub use crate::apple::{translate_apple_strings, translate_stringsdict, translate_xcstrings};
+pub use crate::details::{
+    apply_casing_like, translate_word, translate_word_with, try_translate_word,
+    try_translate_word_with,
+};
+use crate::details::{translate_text, WordBuffers};
+use crate::dialect::OTDOPL;
+pub use crate::dialect::{Dialect, DialectBuilder, VowellessPolicy, YMode};
+pub use crate::error::PigLatinError;
+pub use crate::fluent::translate_fluent;
+pub use crate::glossary::Glossary;
+pub use crate::html::{translate_html, DEFAULT_HTML_ATTRIBUTES};
+pub use crate::json::translate_json;
+pub use crate::markdown::translate_markdown;
+pub use crate::po::translate_po;
+use crate::protect::DEFAULT_PROTECTION;
+pub use crate::protect::{ProtectedClass, Protection};
+pub use crate::pseudo::{PseudoLocalized, PseudoLocalizer};
+pub use crate::source_map::{translate_with_source_map, Mapping, SourceMap};
+pub use crate::subtitles::{translate_srt, translate_webvtt};
+pub use crate::tokens::{tokens, Token, TokenKind};
+pub use crate::translator::Translator;
+pub use crate::untranslate::{
+    untranslate, untranslate_with, RankedSegment, RankedUntranslation, RankedWord, Segment,
+    Untranslation,
+};
+pub use crate::word_model::{WordFrequencies, WordModel};
+
+mod android;
+mod apple;
+mod dialect;
+mod entities;
+mod error;
+mod escape;
+mod fluent;
+mod glossary;
+mod html;
+mod json;
+mod markdown;
+mod markup;
+mod placeholder;
+mod po;
+mod protect;
+mod pseudo;
+mod source_map;
+mod subtitles;
+mod tokens;
+mod translator;
+mod untranslate;
+mod word_model;
 
 #[cfg(test)]
 mod tests {
@@ -185,6 +325,17 @@ mod tests {
         assert_eq!(result, "omelethay");
     }
 
+    #[test]
+    fn into_sinks_like_translate() {
+        let english = "This is all quite easy, is it not?\n\tEarly-Adopters are ecstatic!";
+        let mut into_string = String::new();
+        translate_into(english, &mut into_string).unwrap();
+        assert_eq!(into_string, translate(english));
+        let mut into_bytes = Vec::new();
+        translate_to_io(english, &mut into_bytes).unwrap();
+        assert_eq!(into_bytes, translate(english).as_bytes());
+    }
+
     #[test]
     fn words_is_sentence_if_word_input() {
         for example in ["first", "pigs", "latin", "apple", "banana", "ear", "omelet"] {
@@ -208,6 +359,79 @@ mod tests {
         )
     }
 
+    // Unicode punctuation
+    #[test]
+    fn typographic_quotes() {
+        assert_eq!(translate("“Hello”, she said"), "“Ellohay”, eshay aidsay");
+        assert_eq!(
+            translate("‘pigs’ «latin» „Schwein“"),
+            "‘igspay’ «atinlay» „Einschway“"
+        );
+    }
+
+    #[test]
+    fn dashes_and_ellipses() {
+        assert_eq!(
+            translate("pigs—latin – banana…"),
+            "igspay—atinlay – ananabay…"
+        );
+    }
+
+    #[test]
+    fn cjk_punctuation() {
+        assert_eq!(
+            translate("Pig，latin。「Banana」！"),
+            "Igpay，atinlay。「Ananabay」！"
+        );
+    }
+
+    #[test]
+    fn typographic_apostrophe_like_ascii() {
+        assert_eq!(
+            translate("don’t, no·body, 3.14"),
+            translate("don't, no.body, 3.14")
+                .replace("'", "’")
+                .replacen('.', "·", 1)
+        );
+    }
+
+    // apostrophes
+    #[test]
+    fn contractions() {
+        assert_eq!(translate("don't"), "on'tday");
+        assert_eq!(translate("Shouldn't've"), "Ouldn't'veshay");
+        assert_eq!(translate("O'Brien"), "O'Brienhay");
+        assert_eq!(translate("rock 'n' roll"), "ockray 'nay' ollray");
+        assert_eq!(translate("can’t"), "an’tcay");
+    }
+
+    #[test]
+    fn possessives() {
+        assert_eq!(translate("the pig's tail"), "ethay igpay's ailtay");
+        assert_eq!(translate("JOHN'S"), "OHNJAY'S");
+        assert_eq!(translate("it's"), "ithay's");
+        assert_eq!(translate("Brian’s"), "Ianbray’s");
+        assert_eq!(translate("the pigs' tails"), "ethay igspay' ailstay");
+    }
+
+    #[test]
+    fn apostrophes_as_quotes() {
+        assert_eq!(translate("'Pigs', he said"), "'Igspay', ehay aidsay");
+    }
+
+    #[test]
+    fn contractions_disabled() {
+        let dialect = Dialect::builder().contractions(false).build();
+        assert_eq!(translate_with("don't", &dialect), "onday'tay");
+        assert_eq!(translate_with("O'Brien", &dialect), "Ohay'Ienbray");
+        assert_eq!(translate_with("pig's", &dialect), "igpay'say");
+    }
+
+    #[test]
+    fn emoji_are_not_words() {
+        assert_eq!(translate("pigs🐷 latin 👍"), "igspay🐷 atinlay 👍");
+    }
+
     // edge cases and regressions
     #[test]
     fn empty() {
@@ -218,11 +442,216 @@ mod tests {
 /// implementation details go here, and exposed function's implementations
 /// that are not intended as default entry points
 mod details {
-    /// Return `true` if `c` is an ASCII-vowel, else `false` (uncased).
-    fn is_vowel(c: &char) -> bool {
-        // TODO: explore the wild and wonderous planes of UTF-8 :)
-        let c = c.to_ascii_lowercase();
-        matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+    use std::iter::once;
+
+    use unicode_segmentation::UnicodeSegmentation;
+
+    use crate::dialect::{Dialect, VowellessPolicy, OTDOPL};
+    use crate::protect::split_protected;
+    use crate::{Glossary, PigLatinError, Protection};
+
+    /// Split `text` into alternating word and non-word substrings.
+    ///
+    /// Word boundaries follow the Unicode word boundary rules (UAX #29), and words are
+    /// the substrings between boundaries that contain letters or digits. Within words,
+    /// punctuation used to join words (such as "." and "'" in ASCII, or the typographic
+    /// apostrophe "’") is split off into non-word substrings as well. Thus, for ASCII
+    /// input, words are the contiguous, non-whitespace, non-punctuation substrings.
+    ///
+    /// Yields each non-empty substring together with `true` if it is a word.
+    /// Concatenating the yielded substrings reproduces `text` exactly.
+    ///
+    /// If the `dialect` keeps [contractions](Dialect::contractions) together, words
+    /// joined by an apostrophe (such as "don't") are yielded as a single word.
+    pub fn split_words<'a>(
+        text: &'a str,
+        dialect: &Dialect,
+    ) -> impl Iterator<Item = (&'a str, bool)> + 'a {
+        let (ascii, unicode) = if text.is_ascii() {
+            (Some(split_at_separators(text, is_ascii_separator)), None)
+        } else {
+            (None, Some(split_words_unicode(text)))
+        };
+        let words = ascii
+            .into_iter()
+            .flatten()
+            .chain(unicode.into_iter().flatten());
+        join_contractions(text, words, dialect.contractions())
+    }
+
+    /// The kind of a substring yielded by [`split_pieces`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Piece {
+        /// A word, to be translated.
+        Word,
+        /// Whitespace or punctuation between words.
+        NonWord,
+        /// Text recognised by a [`Protection`], to be copied verbatim.
+        Protected,
+    }
+
+    /// Split `text` into protected text, words, and non-words.
+    ///
+    /// Protected text (see [`Protection`]) is found first, and the text around it
+    /// is split using [`split_words`].
+    /// Concatenating the yielded substrings reproduces `text` exactly.
+    pub fn split_pieces<'a>(
+        text: &'a str,
+        dialect: &'a Dialect,
+        protection: &'a Protection,
+    ) -> impl Iterator<Item = (&'a str, Piece)> + 'a {
+        split_protected(text, protection).flat_map(move |(segment, is_protected)| {
+            let (protected, unprotected) = if is_protected {
+                (Some((segment, Piece::Protected)), None)
+            } else {
+                let words = split_words(segment, dialect).map(|(substring, is_word)| {
+                    let piece = if is_word { Piece::Word } else { Piece::NonWord };
+                    (substring, piece)
+                });
+                (None, Some(words))
+            };
+            protected
+                .into_iter()
+                .chain(unprotected.into_iter().flatten())
+        })
+    }
+
+    /// Return `true` for the ASCII and the typographic apostrophe.
+    fn is_apostrophe(c: char) -> bool {
+        matches!(c, '\'' | '\u{2019}')
+    }
+
+    /// Join words separated by single apostrophes into one word, if `enabled`.
+    ///
+    /// `tokens` are the (contiguous) words and non-words of `text`.
+    fn join_contractions<'a>(
+        text: &'a str,
+        tokens: impl Iterator<Item = (&'a str, bool)>,
+        enabled: bool,
+    ) -> impl Iterator<Item = (&'a str, bool)> {
+        let mut tokens = tokens.peekable();
+        std::iter::from_fn(move || {
+            let (token, is_word) = tokens.next()?;
+            if !enabled || !is_word {
+                return Some((token, is_word));
+            }
+            let start = byte_offset(text, token);
+            let mut end = start + token.len();
+            while let Some(&(separator, false)) = tokens.peek() {
+                let mut separator_chars = separator.chars();
+                let is_single_apostrophe = matches!(
+                    (separator_chars.next(), separator_chars.next()),
+                    (Some(c), None) if is_apostrophe(c)
+                );
+                let after = end + separator.len();
+                if !is_single_apostrophe || !text[after..].starts_with(char::is_alphanumeric) {
+                    break;
+                }
+                tokens.next();
+                end = after;
+                if let Some((word, _)) = tokens.next_if(|&(_, is_word)| is_word) {
+                    end += word.len();
+                }
+            }
+            Some((&text[start..end], true))
+        })
+    }
+
+    /// Return the index of the first byte of `substring` within `text`.
+    ///
+    /// `substring` must be a slice of `text`.
+    pub fn byte_offset(text: &str, substring: &str) -> usize {
+        let offset = (substring.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        debug_assert!(offset + substring.len() <= text.len());
+        offset
+    }
+
+    /// Split a trailing possessive "'s" off `word`, e.g. "pig's" into "pig" and "'s".
+    ///
+    /// Returns `word` and an empty string if there is no possessive "'s".
+    pub fn split_possessive(word: &str) -> (&str, &str) {
+        let Some(core) = word.strip_suffix(['s', 'S']) else {
+            return (word, "");
+        };
+        match core.strip_suffix(is_apostrophe) {
+            Some(core) if !core.is_empty() => word.split_at(core.len()),
+            _ => (word, ""),
+        }
+    }
+
+    fn is_ascii_separator(c: char) -> bool {
+        c.is_ascii_punctuation() || c.is_whitespace()
+    }
+
+    /// Return `true` for ASCII punctuation, and for the punctuation that may join words
+    /// according to UAX #29 (the "MidLetter", "MidNum" and "MidNumLet" classes).
+    fn is_mid_word_punctuation(c: char) -> bool {
+        c.is_ascii_punctuation()
+            || matches!(
+                c,
+                '\u{00B7}' // middle dot
+                    | '\u{0387}' // greek ano teleia
+                    | '\u{055F}' // armenian abbreviation mark
+                    | '\u{05F4}' // hebrew punctuation gershayim
+                    | '\u{2018}' // left single quotation mark
+                    | '\u{2019}' // right single quotation mark, the typographic apostrophe
+                    | '\u{2024}' // one dot leader
+                    | '\u{2027}' // hyphenation point
+                    | '\u{FE13}' // presentation form for vertical colon
+                    | '\u{FE52}' // small full stop
+                    | '\u{FE55}' // small colon
+                    | '\u{FF07}' // fullwidth apostrophe
+                    | '\u{FF0C}' // fullwidth comma
+                    | '\u{FF0E}' // fullwidth full stop
+                    | '\u{FF1A}' // fullwidth colon
+                    | '\u{FF1B}' // fullwidth semicolon
+            )
+    }
+
+    /// Implement [`split_words`] for non-ASCII text.
+    fn split_words_unicode(text: &str) -> impl Iterator<Item = (&str, bool)> {
+        text.split_word_bounds().flat_map(|segment| {
+            let (word, non_word) = if segment.chars().any(char::is_alphanumeric) {
+                (
+                    Some(split_at_separators(segment, is_mid_word_punctuation)),
+                    None,
+                )
+            } else {
+                (None, Some((segment, false)))
+            };
+            word.into_iter().flatten().chain(non_word)
+        })
+    }
+
+    /// Split `text` into words and the single-`char` separators in-between.
+    ///
+    /// Yields each non-empty substring together with `true` if it is a word.
+    fn split_at_separators(
+        text: &str,
+        is_separator: fn(char) -> bool,
+    ) -> impl Iterator<Item = (&str, bool)> {
+        let boundaries = once((0, false))
+            .chain(
+                text.match_indices(is_separator)
+                    .map(|(match_idx, match_str)| (match_idx, match_idx + match_str.len()))
+                    .flat_map(|(match_start, match_end)| {
+                        once((match_start, true)).chain(once((match_end, false)))
+                    }),
+            )
+            .chain(once((text.len(), false)));
+        let mut last_match_idx = 0;
+        let mut last_is_punct_or_ws = false;
+        boundaries.filter_map(move |(match_idx, is_punct_or_ws)| {
+            let from = last_match_idx;
+            let to = match_idx;
+            let from_is_punct_or_ws = last_is_punct_or_ws;
+            last_match_idx = match_idx;
+            last_is_punct_or_ws = is_punct_or_ws;
+            if to <= from {
+                return None;
+            }
+            Some((&text[from..to], !from_is_punct_or_ws))
+        })
     }
 
     #[derive(PartialEq, Debug, Copy, Clone)]
@@ -271,7 +700,7 @@ mod details {
         result
     }
 
-    fn apply_casing_like_inplace(text: &str, casing_of: &str, result: &mut String) -> () {
+    fn apply_casing_like_inplace(text: &str, casing_of: &str, result: &mut String) {
         let mut text_byte_idx = 0;
         let mut last_edit = 0;
         let mut target_case = CharCase::Eh;
@@ -308,11 +737,10 @@ mod details {
                     CharCase::Eh => panic!("{target_case:?} should be unreachable here"),
                 }
                 last_edit = end_edit;
-            } else if text_byte_idx + 1 == text.len() {
-                result.push_str(&text[last_edit..text_byte_idx + 1]);
             }
             text_byte_idx += text_char.len_utf8();
         }
+        result.push_str(&text[last_edit..]);
     }
 
     /// # Translate a single english word into Pig-Latin.
@@ -321,10 +749,11 @@ mod details {
     ///
     /// The input is assumed to be a single word, and this is not checked.
     /// "Single word" means that there are no special characters,  that
-    /// "end the word" in the string. Passing an empty string is UB.
+    /// "end the word" in the string. The result for other inputs (including
+    /// the empty string) is unspecified, but never a panic.
     ///  
     /// **Hint: If you are not fully able to guarantee single-word inputs,
-    /// use [`crate::translate`] instead.**
+    /// use [`crate::translate`], or [`try_translate_word`] to check them, instead.**
     ///
     ///  ## Examples
     ///
@@ -334,21 +763,203 @@ mod details {
     /// assert_eq!(translate_word("Rar"), String::from("Array"));
     /// ```
     pub fn translate_word(english_word: &str) -> String {
+        translate_word_with(english_word, &OTDOPL)
+    }
+
+    /// # Translate a single english word into Pig-Latin, in any dialect.
+    ///
+    /// Like [`translate_word`], but following the rules of `dialect` instead of OTDoPL.
+    /// The same caveats about single-word inputs apply.
+    ///
+    ///  ## Examples
+    ///
+    /// ```rust
+    /// # use pig_latin::{translate_word_with, Dialect};
+    /// let dialect = Dialect::builder().consonant_suffix("ei").build();
+    /// assert_eq!(translate_word_with("Rar", &dialect), String::from("Arrei"));
+    /// ```
+    pub fn translate_word_with(english_word: &str, dialect: &Dialect) -> String {
         // TODO: check speed gain if mutating provided reference instead
-        let mut translated = String::with_capacity(english_word.len() + "hay".len());
-        translate_word_inplace(english_word, &mut translated);
+        let suffix_len = dialect
+            .vowel_suffix()
+            .len()
+            .max(dialect.consonant_suffix().len());
+        let mut translated = String::with_capacity(english_word.len() + suffix_len);
+        let mut moved = String::with_capacity(english_word.len() + suffix_len);
+        translate_word_inplace(english_word, dialect, &mut moved, &mut translated);
         translated
     }
 
-    pub fn translate_word_inplace(english_word: &str, translated: &mut String) -> () {
-        let byte_idx_cut_at = byte_idx_starting_consonants(&english_word);
+    /// # Translate a single english word into Pig-Latin, checking that it is one.
+    ///
+    /// Like [`translate_word`], but returns an error instead of an unspecified result
+    /// if `english_word` is not a single word, as split by [`translate`](crate::translate).
+    ///
+    ///  ## Examples
+    ///
+    /// ```rust
+    /// # use pig_latin::{try_translate_word, PigLatinError};
+    /// assert_eq!(try_translate_word("Rar"), Ok(String::from("Array")));
+    /// assert_eq!(try_translate_word(""), Err(PigLatinError::EmptyInput));
+    /// assert_eq!(
+    ///     try_translate_word("Rar rar"),
+    ///     Err(PigLatinError::EmbeddedSeparator { separator: ' ', byte_idx: 3 })
+    /// );
+    /// assert_eq!(try_translate_word("42"), Err(PigLatinError::NoLetters));
+    /// ```
+    pub fn try_translate_word(english_word: &str) -> Result<String, PigLatinError> {
+        try_translate_word_with(english_word, &OTDOPL)
+    }
+
+    /// # Translate a single english word into Pig-Latin, in any dialect, checking that it is one.
+    ///
+    /// Like [`try_translate_word`], but following the rules of `dialect` instead of OTDoPL.
+    /// Whether a word with an apostrophe (such as "don't") is a single word depends on
+    /// the dialect's [contractions](Dialect::contractions) setting.
+    pub fn try_translate_word_with(
+        english_word: &str,
+        dialect: &Dialect,
+    ) -> Result<String, PigLatinError> {
+        check_single_word(english_word, dialect)?;
+        Ok(translate_word_with(english_word, dialect))
+    }
+
+    /// Check that `text` is a single word containing letters, when split using `dialect`.
+    fn check_single_word(text: &str, dialect: &Dialect) -> Result<(), PigLatinError> {
+        if text.is_empty() {
+            return Err(PigLatinError::EmptyInput);
+        }
+        let mut pieces = split_words(text, dialect);
+        let mut has_word = false;
+        let mut first_separator = None;
+        for (substring, is_word) in &mut pieces {
+            if is_word {
+                has_word = true;
+            } else if first_separator.is_none() {
+                first_separator = Some(substring);
+            }
+            if has_word && first_separator.is_some() {
+                break;
+            }
+        }
+        match first_separator {
+            _ if !has_word => Err(PigLatinError::NoLetters),
+            Some(separator) => Err(PigLatinError::EmbeddedSeparator {
+                separator: separator.chars().next().unwrap_or_default(),
+                byte_idx: byte_offset(text, separator),
+            }),
+            None if !text.chars().any(char::is_alphabetic) => Err(PigLatinError::NoLetters),
+            None => Ok(()),
+        }
+    }
+
+    /// Scratch space for translating words, to be re-used between words.
+    #[derive(Debug, Clone, Default)]
+    pub struct WordBuffers {
+        moved: String,
+        translated: String,
+    }
+
+    /// Translate `english` piece by piece, passing each piece of the translation to `emit`.
+    ///
+    /// Stops at, and returns, the first error returned by `emit`.
+    pub fn translate_text<E>(
+        english: &str,
+        dialect: &Dialect,
+        protection: &Protection,
+        glossary: Option<&Glossary>,
+        buffers: &mut WordBuffers,
+        mut emit: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E> {
+        for (substring, piece) in split_pieces(english, dialect, protection) {
+            if piece == Piece::Word {
+                buffers.translated.clear();
+                translate_word_glossary_inplace(
+                    substring,
+                    dialect,
+                    glossary,
+                    &mut buffers.moved,
+                    &mut buffers.translated,
+                );
+                emit(&buffers.translated)?;
+            } else {
+                emit(substring)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Translate a single word like [`translate_word_inplace`], unless it is in `glossary`.
+    pub fn translate_word_glossary_inplace(
+        english_word: &str,
+        dialect: &Dialect,
+        glossary: Option<&Glossary>,
+        moved: &mut String,
+        translated: &mut String,
+    ) {
+        if let Some(glossary) = glossary {
+            let (core, possessive) = if dialect.contractions() {
+                split_possessive(english_word)
+            } else {
+                (english_word, "")
+            };
+            // e.g. "it's" may be in the glossary as a whole
+            for (core, possessive) in [(english_word, ""), (core, possessive)] {
+                match glossary.get(core) {
+                    None => continue,
+                    Some(None) => translated.push_str(core),
+                    Some(Some(translation)) => {
+                        apply_casing_like_inplace(translation, core, translated)
+                    }
+                }
+                translated.push_str(possessive);
+                return;
+            }
+        }
+        translate_word_inplace(english_word, dialect, moved, translated);
+    }
+
+    /// Translate a single word, appending the translation to `translated`.
+    ///
+    /// `moved` is scratch space, its contents are overwritten.
+    pub fn translate_word_inplace(
+        english_word: &str,
+        dialect: &Dialect,
+        moved: &mut String,
+        translated: &mut String,
+    ) {
+        let (english_word, possessive) = if dialect.contractions() {
+            split_possessive(english_word)
+        } else {
+            (english_word, "")
+        };
+        let byte_idx_cut_at = match byte_idx_starting_consonants(english_word, dialect) {
+            Some(byte_idx_cut_at) => byte_idx_cut_at,
+            None => match dialect.vowelless() {
+                VowellessPolicy::WholeWord => english_word.len(),
+                VowellessPolicy::YAsVowel => {
+                    byte_idx_non_initial_y(english_word).unwrap_or(english_word.len())
+                }
+                VowellessPolicy::Untouched => {
+                    translated.push_str(english_word);
+                    translated.push_str(possessive);
+                    return;
+                }
+            },
+        };
         // starts with a vowel
         if byte_idx_cut_at == 0 {
-            translate_word_starts_voweled(&english_word, translated);
-            return;
+            translate_word_starts_voweled(english_word, dialect.vowel_suffix(), translated);
+        } else {
+            translate_word_starts_consonant(
+                english_word,
+                byte_idx_cut_at,
+                dialect.consonant_suffix(),
+                moved,
+            );
+            apply_casing_like_inplace(moved, english_word, translated);
         }
-        let temp = translate_word_starts_consonant(&english_word, byte_idx_cut_at);
-        apply_casing_like_inplace(&temp, english_word, translated);
+        translated.push_str(possessive);
     }
 
     /// Apply the translation rule for words beginning with a vowel.
@@ -358,10 +969,11 @@ mod details {
     ///
     /// Args:
     ///  - `english_word`: the input
+    ///  - `suffix`: the dialect's suffix for words starting with a vowel
     ///  - `translated`: the mutable output, will be appended to
-    fn translate_word_starts_voweled(english_word: &str, translated: &mut String) -> () {
+    fn translate_word_starts_voweled(english_word: &str, suffix: &str, translated: &mut String) {
         translated.push_str(english_word);
-        translated.push_str("hay");
+        translated.push_str(suffix);
     }
 
     /// Find the index to the byte (UTF-8) at which the first vowel appears in the string.
@@ -369,27 +981,89 @@ mod details {
     /// This is important to the rule for translating words starting with consonants.
     /// This index identifies the cut separating the initial-consonant-substring to move
     /// towards the back, from the unchanged core of the word to be left in place.
-    fn byte_idx_starting_consonants(english_word: &str) -> usize {
-        let mut byte_idx_cut_at = 0;
-        for char in english_word.chars() {
-            if is_vowel(&char) {
-                break;
-            }
-            byte_idx_cut_at += char.len_utf8();
+    ///
+    /// The cut always falls between extended grapheme clusters, so that the moved
+    /// consonants are whole user-perceived characters (e.g. including combining marks).
+    /// A grapheme cluster is a vowel if its first `char` is.
+    ///
+    /// Returns `None` if the word contains no vowel.
+    fn byte_idx_starting_consonants(english_word: &str, dialect: &Dialect) -> Option<usize> {
+        // ASCII words never contain multi-char grapheme clusters (apart from "\r\n")
+        if english_word.is_ascii() {
+            let chars = (0..english_word.len()).map(|idx| (idx, &english_word[idx..idx + 1]));
+            byte_idx_first_vowel_grapheme(chars, english_word, dialect)
+        } else {
+            let graphemes = english_word.grapheme_indices(true);
+            byte_idx_first_vowel_grapheme(graphemes, english_word, dialect)
         }
+    }
 
+    /// Implement [`byte_idx_starting_consonants`] for the given grapheme clusters of a word.
+    fn byte_idx_first_vowel_grapheme<'a>(
+        graphemes: impl Iterator<Item = (usize, &'a str)>,
+        english_word: &str,
+        dialect: &Dialect,
+    ) -> Option<usize> {
         // "q" on its own is not usual in english. To make translations more
         // pronouncable, move "qu" as a unit (the "first vowel" is then not "u",
-        // but the following character, even if not a vowel itself)
-        if english_word.len() > byte_idx_cut_at {
-            let mut chars = english_word[..byte_idx_cut_at + 1].chars();
-            if chars.next().unwrap().to_ascii_lowercase() == 'q'
-                && chars.next().unwrap().to_ascii_lowercase() == 'u'
-            {
-                byte_idx_cut_at += 'u'.len_utf8();
+        // but the character following the unit, even if not a vowel itself).
+        // Dialects may list other such onset units.
+        let unit_len = onset_unit_len(english_word, dialect).unwrap_or(0);
+        let mut first_vowel = None;
+        let mut after_unit = None;
+        for (grapheme_idx, (byte_idx, grapheme)) in graphemes.enumerate() {
+            let Some(first_char) = grapheme.chars().next() else {
+                continue;
             };
+            if after_unit.is_none() && byte_idx >= unit_len {
+                after_unit = Some(byte_idx);
+            }
+            if first_vowel.is_none() && dialect.is_vowel_at(first_char, grapheme_idx) {
+                first_vowel = Some(byte_idx);
+            }
+            if first_vowel.is_some() && after_unit.is_some() {
+                break;
+            }
+        }
+        let first_vowel = first_vowel?;
+        if first_vowel < unit_len {
+            Some(after_unit.unwrap_or(english_word.len()))
+        } else {
+            Some(first_vowel)
         }
-        byte_idx_cut_at
+    }
+
+    /// Return the byte length of the longest onset unit of `dialect` that starts
+    /// `english_word` (ignoring case), if any.
+    fn onset_unit_len(english_word: &str, dialect: &Dialect) -> Option<usize> {
+        dialect
+            .onset_units()
+            .iter()
+            .filter_map(|unit| {
+                let mut word_chars = english_word.char_indices();
+                for unit_char in unit.chars() {
+                    let (_, c) = word_chars.next()?;
+                    if !same_uncased(c, unit_char) {
+                        return None;
+                    }
+                }
+                Some(word_chars.next().map_or(english_word.len(), |(idx, _)| idx))
+            })
+            .max()
+    }
+
+    /// Find the index of the first "y" (or "Y") that is not at the start of the word.
+    fn byte_idx_non_initial_y(english_word: &str) -> Option<usize> {
+        english_word
+            .grapheme_indices(true)
+            .skip(1)
+            .find(|(_, grapheme)| grapheme.starts_with(['y', 'Y']))
+            .map(|(byte_idx, _)| byte_idx)
+    }
+
+    /// Return `true` if `c` is `lower` when lower-cased.
+    fn same_uncased(c: char, lower: char) -> bool {
+        c == lower || c.to_lowercase().eq(once(lower))
     }
 
     /// Translate a word according to the rule for words starting with consonants.
@@ -400,12 +1074,18 @@ mod details {
     /// Args:
     ///  - `english_word`: the input string
     ///  - `byte_idx_cut_at`: the index into the string at which the first vowel is found
-    fn translate_word_starts_consonant(english_word: &str, byte_idx_cut_at: usize) -> String {
-        let mut translated = String::with_capacity(english_word.len() + "ay".len());
+    ///  - `suffix`: the dialect's suffix for words starting with a consonant
+    ///  - `translated`: the mutable output, will be overwritten
+    fn translate_word_starts_consonant(
+        english_word: &str,
+        byte_idx_cut_at: usize,
+        suffix: &str,
+        translated: &mut String,
+    ) {
+        translated.clear();
         translated.push_str(&english_word[byte_idx_cut_at..]);
         translated.push_str(&english_word[..byte_idx_cut_at]);
-        translated.push_str("ay");
-        translated
+        translated.push_str(suffix);
     }
 
     #[cfg(test)]
@@ -415,14 +1095,15 @@ mod details {
         // units
         #[test]
         fn _is_vowel() {
-            assert_eq!(is_vowel(&'a'), true);
-            assert_eq!(is_vowel(&'u'), true);
-            assert_eq!(is_vowel(&'k'), false);
-            assert_eq!(is_vowel(&'q'), false);
-            assert_eq!(is_vowel(&'f'), false);
-            assert_eq!(is_vowel(&' '), false);
-            assert_eq!(is_vowel(&'.'), false);
-            assert_eq!(is_vowel(&'7'), false);
+            let is_vowel = |c: &char| OTDOPL.is_vowel(*c);
+            assert!(is_vowel(&'a'));
+            assert!(is_vowel(&'u'));
+            assert!(!is_vowel(&'k'));
+            assert!(!is_vowel(&'q'));
+            assert!(!is_vowel(&'f'));
+            assert!(!is_vowel(&' '));
+            assert!(!is_vowel(&'.'));
+            assert!(!is_vowel(&'7'));
         }
 
         #[test]
@@ -446,6 +1127,14 @@ mod details {
             assert_eq!(apply_casing_like("Straße", "TROLOLOLO"), "STRASSE");
         }
 
+        #[test]
+        fn copy_casing_multibyte_end() {
+            assert_eq!(apply_casing_like("café", "abcd"), "café");
+            assert_eq!(apply_casing_like("é", "a"), "é");
+            assert_eq!(apply_casing_like("café", "Abcd"), "Café");
+            assert_eq!(apply_casing_like("cafÉ", "abcd"), "café");
+        }
+
         #[test]
         fn copy_casing_empty() {
             assert_eq!(apply_casing_like("", "Hello"), "");
@@ -459,6 +1148,32 @@ mod details {
             assert_eq!(translate_word("quaint"), "aintquay");
         }
 
+        #[test]
+        fn translate_word_onset_units() {
+            let dialect = Dialect::builder()
+                .onset_unit("squ")
+                .onset_unit("Sch")
+                .onset_unit("thr")
+                .build();
+            assert_eq!(translate_word_with("squeal", &dialect), "ealsquay");
+            assert_eq!(translate_word_with("Squid", &dialect), "Idsquay");
+            assert_eq!(translate_word_with("schema", &dialect), "emaschay");
+            assert_eq!(translate_word_with("SCHOOL", &dialect), "OOLSCHAY");
+            assert_eq!(translate_word_with("three", &dialect), "eethray");
+            assert_eq!(translate_word_with("quit", &dialect), "itquay");
+            // units are only moved as a whole if they start the word
+            assert_eq!(translate_word_with("ask", &dialect), "askhay");
+            assert_eq!(translate_word_with("mesquite", &dialect), "esquitemay");
+            // the longest unit wins
+            let dialect = Dialect::builder().onset_unit("q").onset_unit("qua").build();
+            assert_eq!(translate_word_with("quack", &dialect), "ckquaay");
+            assert_eq!(translate_word_with("quest", &dialect), "estquay");
+            // without "qu", "squeal" is cut at the "u"
+            assert_eq!(translate_word("squeal"), "uealsqay");
+            let dialect = Dialect::builder().no_onset_units().build();
+            assert_eq!(translate_word_with("quit", &dialect), "uitqay");
+        }
+
         #[test]
         fn translate_word_upper_qu() {
             assert_eq!(translate_word("QUERY"), "ERYQUAY");
@@ -466,5 +1181,169 @@ mod details {
             assert_eq!(translate_word("qUeRy"), "eRyQuay");
             assert_eq!(translate_word("Query"), "Eryquay");
         }
+
+        #[test]
+        fn translate_word_diacritics_precomposed() {
+            assert_eq!(translate_word("école"), "écolehay");
+            assert_eq!(translate_word("Ürsula"), "Ürsulahay");
+            assert_eq!(translate_word("ångström"), "ångströmhay");
+            assert_eq!(translate_word("Øre"), "Ørehay");
+            assert_eq!(translate_word("crème"), "èmecray");
+            assert_eq!(translate_word("Naïve"), "Aïvenay");
+            assert_eq!(translate_word("Ça"), "Açay");
+        }
+
+        #[test]
+        fn translate_word_diacritics_combining() {
+            assert_eq!(translate_word("e\u{301}cole"), "e\u{301}colehay");
+            assert_eq!(translate_word("U\u{308}rsula"), "U\u{308}rsulahay");
+            assert_eq!(
+                translate_word("a\u{30a}ngstro\u{308}m"),
+                "a\u{30a}ngstro\u{308}mhay"
+            );
+            assert_eq!(translate_word("cre\u{300}me"), "e\u{300}mecray");
+        }
+
+        #[test]
+        fn translate_word_grapheme_clusters() {
+            // the combining cedilla stays with its "c"
+            assert_eq!(translate_word("c\u{327}a"), "ac\u{327}ay");
+            assert_eq!(translate_word("fac\u{327}ade"), "ac\u{327}adefay");
+            // the combining diaeresis stays with the "u" of "qu"
+            assert_eq!(translate_word("qu\u{308}ery"), "eryqu\u{308}ay");
+            // emoji ZWJ sequences are moved as a whole
+            assert_eq!(translate_word("👩\u{200d}🌾arm"), "arm👩\u{200d}🌾ay");
+            assert_eq!(
+                translate_word("🏳\u{fe0f}\u{200d}🌈"),
+                "🏳\u{fe0f}\u{200d}🌈ay"
+            );
+            // zero-width joiners stay with the consonant they follow
+            assert_eq!(translate_word("b\u{200d}ab"), "abb\u{200d}ay");
+        }
+
+        #[test]
+        fn try_translate_word_checks() {
+            assert_eq!(try_translate_word("Pig").unwrap(), "Igpay");
+            assert_eq!(try_translate_word("don't").unwrap(), "on'tday");
+            assert_eq!(try_translate_word("mp3").unwrap(), "mp3ay");
+            assert_eq!(try_translate_word(""), Err(PigLatinError::EmptyInput));
+            assert_eq!(try_translate_word("123"), Err(PigLatinError::NoLetters));
+            assert_eq!(try_translate_word("?!"), Err(PigLatinError::NoLetters));
+            assert_eq!(try_translate_word("  "), Err(PigLatinError::NoLetters));
+            for (text, separator, byte_idx) in [
+                ("pig latin", ' ', 3),
+                (" pig", ' ', 0),
+                ("pig!", '!', 3),
+                ("Igpay-Atinlay", '-', 5),
+                ("naïve—pig", '—', 6),
+                ("pig\n", '\n', 3),
+            ] {
+                assert_eq!(
+                    try_translate_word(text),
+                    Err(PigLatinError::EmbeddedSeparator {
+                        separator,
+                        byte_idx
+                    }),
+                    "{text:?}"
+                );
+            }
+            let dialect = Dialect::builder().contractions(false).build();
+            assert_eq!(
+                try_translate_word_with("don't", &dialect),
+                Err(PigLatinError::EmbeddedSeparator {
+                    separator: '\'',
+                    byte_idx: 3
+                })
+            );
+        }
+
+        #[test]
+        fn translate_word_vowelless() {
+            let words = ["rhythm", "Hmm", "nth", "TV", "psst", "myth's", "y"];
+            let translate_all = |vowelless| -> Vec<_> {
+                let dialect = Dialect::builder().vowelless(vowelless).build();
+                words
+                    .iter()
+                    .map(|word| translate_word_with(word, &dialect))
+                    .collect()
+            };
+            assert_eq!(
+                translate_all(VowellessPolicy::WholeWord),
+                ["rhythmay", "Hmmay", "nthay", "TVAY", "psstay", "mythay's", "yay"]
+            );
+            assert_eq!(
+                translate_all(VowellessPolicy::YAsVowel),
+                ["ythmrhay", "Hmmay", "nthay", "TVAY", "psstay", "ythmay's", "yay"]
+            );
+            assert_eq!(
+                translate_all(VowellessPolicy::Untouched),
+                ["rhythm", "Hmm", "nth", "TV", "psst", "myth's", "y"]
+            );
+            // words with vowels are not affected
+            let dialect = Dialect::builder()
+                .vowelless(VowellessPolicy::Untouched)
+                .build();
+            assert_eq!(translate_word_with("qu", &dialect), "quay");
+            assert_eq!(translate_word_with("yes", &dialect), "esyay");
+        }
+
+        #[test]
+        fn translate_word_y_positional() {
+            let dialect = Dialect::builder().y_mode(crate::YMode::Positional).build();
+            for (english, expected) in [
+                // leading "y" is a consonant
+                ("yellow", "ellowyay"),
+                ("yes", "esyay"),
+                ("you", "ouyay"),
+                ("young", "oungyay"),
+                ("Yvonne", "Onneyvay"),
+                // "y" after consonants is the first vowel
+                ("style", "ylestay"),
+                ("rhythm", "ythmrhay"),
+                ("my", "ymay"),
+                ("by", "ybay"),
+                ("fly", "yflay"),
+                ("shy", "yshay"),
+                ("why", "ywhay"),
+                ("gym", "ymgay"),
+                ("lynx", "ynxlay"),
+                ("psych", "ychpsay"),
+                ("crystal", "ystalcray"),
+                ("system", "ystemsay"),
+                ("STYLE", "YLESTAY"),
+                ("Dryad", "Yaddray"),
+                // "y" after a vowel changes nothing
+                ("boy", "oybay"),
+                ("beyond", "eyondbay"),
+                ("happy", "appyhay"),
+                ("eye", "eyehay"),
+                ("quay", "ayquay"),
+            ] {
+                assert_eq!(
+                    translate_word_with(english, &dialect),
+                    expected,
+                    "{english}"
+                );
+            }
+            // OTDoPL
+            assert_eq!(translate_word("style"), "estylay");
+            assert_eq!(translate_word("yellow"), "ellowyay");
+        }
+
+        #[test]
+        fn translate_word_dialects() {
+            let dialect = Dialect::builder()
+                .vowel_suffix("way")
+                .consonant_suffix("ei")
+                .no_onset_units()
+                .onset_unit("gu")
+                .vowels("aeiouy")
+                .build();
+            assert_eq!(translate_word_with("apple", &dialect), "appleway");
+            assert_eq!(translate_word_with("pig", &dialect), "igpei");
+            assert_eq!(translate_word_with("query", &dialect), "ueryqei");
+            assert_eq!(translate_word_with("Guest", &dialect), "Estguei");
+            assert_eq!(translate_word_with("rhythm", &dialect), "ythmrhei");
+        }
     }
 }
@@ -0,0 +1,599 @@
+//! Translating Markdown documents.
+
+use std::collections::HashSet;
+
+use crate::html::translate_text;
+use crate::Translator;
+
+/// The HTML elements starting an HTML block, which is copied as-is.
+const HTML_BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "style",
+    "summary",
+    "table",
+    "ul",
+];
+
+/// # Translate the prose of a Markdown document into Pig-Latin.
+///
+/// Translates paragraphs, headings, list items, block quotes and table cells, including
+/// emphasis and the text of links (and the descriptions of images). Everything else is
+/// copied byte for byte, so that diffs stay minimal:
+///  - front matter (between `---` or `+++` lines at the start of the document),
+///  - fenced and indented code blocks, and code spans,
+///  - the attribute blocks of headings (such as `{#pig-id}`), which anchors refer to,
+///  - the destinations and titles of links, autolinks, and link reference definitions,
+///  - the labels of reference links, and the text of links that is also their label
+///    (such as `[Pig Farm]`, with a definition `[pig farm]: https://...`),
+///  - HTML blocks (such as `<div>`s), inline HTML tags, and character references
+///    (such as `&nbsp;`).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_markdown, Translator};
+/// let markdown = "---\ntitle: Pigs\n---\n## Feeding *your* pig\n
+/// Call `feed(pig)`, as the [manual](https://example.com/feed \"Feeding\") says:
+///
+/// ~~~rust
+/// feed(pig);
+/// ~~~
+/// ";
+/// assert_eq!(
+///     translate_markdown(markdown, &mut Translator::default()),
+///     "---\ntitle: Pigs\n---\n## Eedingfay *ouryay* igpay\n
+/// Allcay `feed(pig)`, ashay ethay [anualmay](https://example.com/feed \"Feeding\") ayssay:
+///
+/// ~~~rust
+/// feed(pig);
+/// ~~~
+/// "
+/// );
+/// ```
+pub fn translate_markdown(markdown: &str, translator: &mut Translator) -> String {
+    let blocks = blocks(markdown);
+    let labels: HashSet<String> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            Block::Definition(label, _) => Some(normalize_label(label)),
+            _ => None,
+        })
+        .collect();
+    let mut translated = String::with_capacity(markdown.len() * 2);
+    for block in blocks {
+        match block {
+            Block::Prose(text) => translate_inline(text, translator, &labels, &mut translated),
+            Block::Verbatim(text) | Block::Definition(_, text) => translated.push_str(text),
+        }
+    }
+    translated
+}
+
+/// A part of a Markdown document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block<'a> {
+    /// Lines of inline content, such as a paragraph or a heading.
+    Prose(&'a str),
+    /// Lines that are copied as-is, such as code blocks or blank lines.
+    Verbatim(&'a str),
+    /// A link reference definition: its label, and its line.
+    Definition(&'a str, &'a str),
+}
+
+/// Split `markdown` into blocks, by lines.
+fn blocks(markdown: &str) -> Vec<Block<'_>> {
+    let lines: Vec<&str> = markdown.split_inclusive('\n').collect();
+    let mut blocks = Vec::new();
+    let mut idx = 0;
+    // the byte index of the next block
+    let mut end = 0;
+    let mut previous_blank = true;
+    let mut in_list = false;
+
+    if let Some(fence) = lines.first().map(|line| line.trim_end()) {
+        if fence == "---" || fence == "+++" {
+            let closing = lines[1..].iter().position(|line| {
+                let line = line.trim_end();
+                line == fence || (fence == "---" && line == "...")
+            });
+            if let Some(closing) = closing {
+                idx = closing + 2;
+                end = lines[..idx].iter().map(|line| line.len()).sum();
+                blocks.push(Block::Verbatim(&markdown[..end]));
+            }
+        }
+    }
+
+    while idx < lines.len() {
+        let line = lines[idx];
+        let content = &line[container_prefix_len(line)..];
+        let trimmed = content.trim_start();
+        let indent = content.len() - trimmed.len();
+        let kind = if trimmed.trim_end().is_empty() {
+            LineKind::Blank
+        } else if let Some(fence) = opening_fence(trimmed) {
+            LineKind::Fence(fence)
+        } else if (indent >= 4 || content.starts_with('\t')) && !in_list && previous_blank {
+            LineKind::IndentedCode
+        } else if indent < 4 && definition_label(trimmed).is_some() {
+            LineKind::Definition
+        } else if indent < 4 && html_block_end(trimmed).is_some() {
+            LineKind::Html
+        } else if indent < 4 && trimmed.starts_with('#') {
+            LineKind::Heading
+        } else {
+            LineKind::Prose
+        };
+
+        let count = match kind {
+            LineKind::Fence(fence) => {
+                let closing = lines[idx + 1..].iter().position(|line| {
+                    let content = line[container_prefix_len(line)..].trim();
+                    content.starts_with(fence)
+                        && content
+                            .trim_start_matches(fence.as_bytes()[0] as char)
+                            .is_empty()
+                });
+                closing.map_or(lines.len() - idx, |closing| closing + 2)
+            }
+            LineKind::IndentedCode => {
+                1 + lines[idx + 1..]
+                    .iter()
+                    .take_while(|line| {
+                        let content = &line[container_prefix_len(line)..];
+                        content.trim().is_empty()
+                            || content.starts_with("    ")
+                            || content.starts_with('\t')
+                    })
+                    .count()
+            }
+            LineKind::Html => {
+                let end_marker = html_block_end(trimmed).unwrap_or_default();
+                let closing = lines[idx..].iter().position(|line| match end_marker {
+                    "" => line.trim().is_empty(),
+                    marker => line.to_ascii_lowercase().contains(marker),
+                });
+                match (end_marker, closing) {
+                    // the blank line ends the block, but is not part of it
+                    ("", Some(closing)) => closing,
+                    (_, Some(closing)) => closing + 1,
+                    (_, None) => lines.len() - idx,
+                }
+            }
+            LineKind::Prose => {
+                1 + lines[idx + 1..]
+                    .iter()
+                    .take_while(|line| {
+                        let content = &line[container_prefix_len(line)..];
+                        let trimmed = content.trim_start();
+                        !trimmed.trim_end().is_empty()
+                            && opening_fence(trimmed).is_none()
+                            && !trimmed.starts_with('#')
+                            && html_block_end(trimmed).is_none()
+                    })
+                    .count()
+            }
+            LineKind::Blank | LineKind::Definition | LineKind::Heading => 1,
+        };
+        let len: usize = lines[idx..idx + count].iter().map(|line| line.len()).sum();
+        let text = &markdown[end..end + len];
+        match kind {
+            LineKind::Prose => blocks.push(Block::Prose(text)),
+            LineKind::Heading => {
+                // attribute blocks, such as "{#id}", are not prose
+                let attributes = heading_attributes_start(text).unwrap_or(text.len());
+                blocks.push(Block::Prose(&text[..attributes]));
+                blocks.push(Block::Verbatim(&text[attributes..]));
+            }
+            LineKind::Definition => blocks.push(Block::Definition(
+                definition_label(trimmed).unwrap_or_default(),
+                text,
+            )),
+            _ => blocks.push(Block::Verbatim(text)),
+        }
+        end += len;
+        idx += count;
+
+        if kind != LineKind::Blank {
+            // lines indented after a list item continue it, rather than being code
+            in_list = list_marker_len(trimmed).is_some() || in_list && indent > 0;
+        }
+        previous_blank = kind == LineKind::Blank;
+    }
+    blocks
+}
+
+/// Return the start of the attribute block (such as "{#id .class}") at the end of the
+/// heading `line`, if any.
+fn heading_attributes_start(line: &str) -> Option<usize> {
+    let content = line.trim_end();
+    let start = content.rfind('{')?;
+    let attributes = &content[start..];
+    let is_attributes = attributes[1..].starts_with(['#', '.', ':'])
+        && attributes.find('}') == Some(attributes.len() - 1)
+        && content[..start].ends_with([' ', '\t']);
+    is_attributes.then_some(start)
+}
+
+/// The kinds of lines starting blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Blank,
+    /// An opening code fence, with its backticks or tildes.
+    Fence(&'static str),
+    IndentedCode,
+    Definition,
+    Html,
+    Heading,
+    Prose,
+}
+
+/// Return the length of the block quote markers (">") `line` starts with.
+fn container_prefix_len(line: &str) -> usize {
+    let mut pos = 0;
+    loop {
+        let rest = &line[pos..];
+        let trimmed = rest.trim_start_matches(' ');
+        if rest.len() - trimmed.len() > 3 || !trimmed.starts_with('>') {
+            return pos;
+        }
+        pos += rest.len() - trimmed.len() + 1;
+        if line[pos..].starts_with(' ') {
+            pos += 1;
+        }
+    }
+}
+
+/// Return the fence of the code block `line` opens, if any, e.g. "```" or "~~~~".
+fn opening_fence(line: &str) -> Option<&'static str> {
+    const FENCES: [&str; 8] = [
+        "``````", "`````", "````", "```", "~~~~~~", "~~~~~", "~~~~", "~~~",
+    ];
+    let fence = FENCES.into_iter().find(|fence| line.starts_with(fence))?;
+    // the info string of backtick fences cannot contain backticks
+    (fence.starts_with('~') || !line[fence.len()..].contains('`')).then_some(fence)
+}
+
+/// Return the label of the link reference definition `line` starts, if any, e.g. "pig"
+/// for "[pig]: https://example.com".
+fn definition_label(line: &str) -> Option<&str> {
+    let label_len = line.strip_prefix('[')?.find(']')?;
+    let label = &line[1..1 + label_len];
+    let rest = line[label_len + 2..].strip_prefix(':')?;
+    (!label.trim().is_empty() && !label.starts_with('^') && !rest.trim().is_empty())
+        .then_some(label)
+}
+
+/// Return the marker ending the HTML block `line` starts, if any: "" for a blank line.
+fn html_block_end(line: &str) -> Option<&'static str> {
+    if line.starts_with("<!--") {
+        return Some("-->");
+    }
+    let name = line.strip_prefix("</").or_else(|| line.strip_prefix('<'))?;
+    let name_len = name
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(name.len());
+    let after_name = &name[name_len..];
+    if !(after_name.is_empty() || after_name.starts_with([' ', '\t', '\r', '\n', '>', '/'])) {
+        return None;
+    }
+    let name = name[..name_len].to_ascii_lowercase();
+    match name.as_str() {
+        "pre" => Some("</pre>"),
+        "script" => Some("</script>"),
+        "style" => Some("</style>"),
+        name if HTML_BLOCK_ELEMENTS.contains(&name) => Some(""),
+        _ => None,
+    }
+}
+
+/// Return the length of the list marker `line` starts with, if any, e.g. "- " or "1. ".
+fn list_marker_len(line: &str) -> Option<usize> {
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    let marker_len = match line.as_bytes().get(digits) {
+        Some(b'.' | b')') if (1..10).contains(&digits) => digits + 1,
+        Some(b'-' | b'+' | b'*') if digits == 0 => 1,
+        _ => return None,
+    };
+    line[marker_len..]
+        .starts_with([' ', '\t', '\r', '\n'])
+        .then_some(marker_len)
+}
+
+/// Normalize a link label for matching, as Markdown does: ignoring case and runs of
+/// whitespace.
+fn normalize_label(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Translate the inline content `text`, copying code spans, link destinations,
+/// reference labels and HTML as-is.
+fn translate_inline(
+    text: &str,
+    translator: &mut Translator,
+    labels: &HashSet<String>,
+    output: &mut String,
+) {
+    let bytes = text.as_bytes();
+    // the start of the text that is not translated yet
+    let mut pending = 0;
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        let (verbatim_len, link_text): (usize, Option<(usize, usize)>) = match bytes[pos] {
+            b'\\' => {
+                // escaped characters are text, but do not start anything
+                pos += 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+                continue;
+            }
+            b'`' => {
+                let ticks = rest.bytes().take_while(|&b| b == b'`').count();
+                match code_span_len(rest, ticks) {
+                    Some(len) => (len, None),
+                    None => {
+                        pos += ticks;
+                        continue;
+                    }
+                }
+            }
+            b'<' => match inline_html_len(rest) {
+                Some(len) => (len, None),
+                None => {
+                    pos += 1;
+                    continue;
+                }
+            },
+            b'[' | b'!' => match link(rest, labels) {
+                Some(Link::Verbatim(len)) => (len, None),
+                Some(Link::Text(text_start, text_end, len)) => (len, Some((text_start, text_end))),
+                None => {
+                    pos += 1;
+                    continue;
+                }
+            },
+            _ => {
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+                continue;
+            }
+        };
+        translate_text(&text[pending..pos], translator, output);
+        match link_text {
+            Some((text_start, text_end)) => {
+                output.push_str(&rest[..text_start]);
+                translate_inline(&rest[text_start..text_end], translator, labels, output);
+                output.push_str(&rest[text_end..verbatim_len]);
+            }
+            None => output.push_str(&rest[..verbatim_len]),
+        }
+        pos += verbatim_len;
+        pending = pos;
+    }
+    translate_text(&text[pending..], translator, output);
+}
+
+/// Return the length of the code span `text` starts with, opened by `ticks` backticks.
+fn code_span_len(text: &str, ticks: usize) -> Option<usize> {
+    let mut pos = ticks;
+    while let Some(offset) = text[pos..].find('`') {
+        let start = pos + offset;
+        let run = text[start..].bytes().take_while(|&b| b == b'`').count();
+        if run == ticks {
+            return Some(start + run);
+        }
+        pos = start + run;
+    }
+    None
+}
+
+/// Return the length of the autolink, HTML tag or comment `text` starts with, if any.
+fn inline_html_len(text: &str) -> Option<usize> {
+    if let Some(comment) = text.strip_prefix("<!--") {
+        return comment.find("-->").map(|len| 4 + len + 3);
+    }
+    let len = text.find('>')? + 1;
+    let inner = &text[1..len - 1];
+    let is_autolink = !inner.contains(char::is_whitespace) && inner.contains([':', '@']);
+    let name = inner.strip_prefix('/').unwrap_or(inner);
+    let is_tag = name.starts_with(|c: char| c.is_ascii_alphabetic()) && !inner.contains('<');
+    (is_autolink || is_tag).then_some(len)
+}
+
+/// The parts of a link.
+enum Link {
+    /// A link that is copied as-is, of this length.
+    Verbatim(usize),
+    /// A link whose text, from the first to the second byte index, is translated,
+    /// of the given length.
+    Text(usize, usize, usize),
+}
+
+/// Parse the link or image `text` starts with, if any.
+fn link(text: &str, labels: &HashSet<String>) -> Option<Link> {
+    let text_start = if text.starts_with("![") { 2 } else { 1 };
+    if !text[..text_start].ends_with('[') {
+        return None;
+    }
+    let text_end = text_start + closing_bracket(&text[text_start..])?;
+    let label = &text[text_start..text_end];
+    let after = &text[text_end + 1..];
+    if label.starts_with('^') || matches!(label, " " | "x" | "X") {
+        // footnote references, and task list items
+        return Some(Link::Verbatim(text_end + 1));
+    }
+    if after.starts_with('(') {
+        let tail_len = destination_len(after)?;
+        return Some(Link::Text(text_start, text_end, text_end + 1 + tail_len));
+    }
+    if let Some(reference) = after.strip_prefix('[') {
+        let reference_len = closing_bracket(reference)? + 2;
+        if reference_len == 2 && labels.contains(&normalize_label(label)) {
+            // collapsed references: "[label][]"
+            return Some(Link::Verbatim(text_end + 1 + 2));
+        }
+        return Some(Link::Text(
+            text_start,
+            text_end,
+            text_end + 1 + reference_len,
+        ));
+    }
+    // shortcut references: "[label]"
+    labels
+        .contains(&normalize_label(label))
+        .then_some(Link::Verbatim(text_end + 1))
+}
+
+/// Return the byte index of the "]" closing the bracket before `text`.
+fn closing_bracket(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = text.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some(idx),
+            ']' => depth -= 1,
+            '`' => {
+                let ticks = text[idx..].bytes().take_while(|&b| b == b'`').count();
+                let len = code_span_len(&text[idx..], ticks).unwrap_or(ticks);
+                while chars
+                    .clone()
+                    .next()
+                    .is_some_and(|(next, _)| next < idx + len)
+                {
+                    chars.next();
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Return the length of the link destination (and title) `text` starts with, from "("
+/// to ")".
+fn destination_len(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut chars = text.char_indices().skip(1);
+    while let Some((idx, c)) = chars.next() {
+        match (quote, c) {
+            (_, '\\') => {
+                chars.next();
+            }
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '<') => {
+                let len = text[idx..].find('>')?;
+                while chars
+                    .clone()
+                    .next()
+                    .is_some_and(|(next, _)| next <= idx + len)
+                {
+                    chars.next();
+                }
+            }
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return Some(idx + 1),
+            (None, ')') => depth -= 1,
+            (None, '\n') if text[..idx].ends_with('\n') => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(markdown: &str) -> String {
+        translate_markdown(markdown, &mut Translator::default())
+    }
+
+    #[test]
+    fn code() {
+        let markdown = "Use `pig` or ``a ` pig``, not `pig.\n\n    indented code\n    pig\n\nText\n    continued\n\n> ~~~\n> pig\n> ~~~\n>\n> Pig\n\n```\nunclosed pig\n";
+        assert_eq!(
+            translate(markdown),
+            "Usehay `pig` orhay ``a ` pig``, otnay `igpay.\n\n    indented code\n    pig\n\nExttay\n    ontinuedcay\n\n> ~~~\n> pig\n> ~~~\n>\n> Igpay\n\n```\nunclosed pig\n"
+        );
+    }
+
+    #[test]
+    fn links() {
+        let markdown = "See [the *pig*](<./a b.md> \"A (pig)\"), ![a pig](pig.png), [Pig Farm], [pig farm][], [farm][Pig Farm], [nothing], <https://pig.farm> and <b>bold</b>[^1].\n\n[Pig  farm]: https://pig.farm \"Pig farm\"\n[^1]: A pig.\n";
+        assert_eq!(
+            translate(markdown),
+            "Eesay [ethay *igpay*](<./a b.md> \"A (pig)\"), ![ahay igpay](pig.png), [Pig Farm], [pig farm][], [armfay][Pig Farm], [othingnay], <https://pig.farm> andhay <b>oldbay</b>[^1].\n\n[Pig  farm]: https://pig.farm \"Pig farm\"\n[^1]: Ahay igpay.\n"
+        );
+    }
+
+    #[test]
+    fn lists_tables_and_html() {
+        let markdown = "- [x] Feed pig\n- Clean\n\n    the pen\n\n| Pig | Age |\n|-----|----:|\n| Bob | 3 |\n\n<div class=\"pig\">\nPig\n</div>\n\n<!-- a\npig -->\nPig &amp; co\n";
+        assert_eq!(
+            translate(markdown),
+            "- [x] Eedfay igpay\n- Eanclay\n\n    ethay enpay\n\n| Igpay | Agehay |\n|-----|----:|\n| Obbay | 3 |\n\n<div class=\"pig\">\nPig\n</div>\n\n<!-- a\npig -->\nIgpay &amp; ocay\n"
+        );
+    }
+
+    #[test]
+    fn heading_attributes() {
+        let markdown = "# Pig {#pig-id}\n## Pen {.wide #pen}  \r\n> ### Mud {: #mud}\n# Pig {not attributes}\n# Pig{#glued}\n";
+        assert_eq!(
+            translate(markdown),
+            "# Igpay {#pig-id}\n## Enpay {.wide #pen}  \r\n> ### Udmay {: #mud}\n# Igpay {otnay attributeshay}\n# Igpay{#uedglay}\n"
+        );
+    }
+
+    #[test]
+    fn front_matter() {
+        for (markdown, expected) in [
+            (
+                "+++\ntitle = \"Pig\"\n+++\nPig\n",
+                "+++\ntitle = \"Pig\"\n+++\nIgpay\n",
+            ),
+            (
+                "---\ntitle: Pig\n...\n\nPig\n",
+                "---\ntitle: Pig\n...\n\nIgpay\n",
+            ),
+            // not closed: a thematic break
+            ("---\nPig\n", "---\nIgpay\n"),
+            ("\r\n# Pig\r\n", "\r\n# Igpay\r\n"),
+        ] {
+            assert_eq!(translate(markdown), expected, "{markdown:?}");
+        }
+    }
+}
@@ -0,0 +1,508 @@
+//! Scanning XML (and HTML) into tags and text, for the translators of markup formats.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::entities::named_reference;
+use crate::PigLatinError;
+
+/// A piece of markup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Node<'a> {
+    /// Text between tags, with its entities undecoded.
+    Text(&'a str),
+    /// A start tag, such as `<b>` or `<br/>`.
+    StartTag(Tag<'a>),
+    /// An end tag, such as `</b>`.
+    EndTag(Tag<'a>),
+    /// A comment, CDATA section, processing instruction or declaration.
+    Other(&'a str),
+}
+
+/// A start or end tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Tag<'a> {
+    /// The tag as written, from "<" to ">".
+    pub(crate) raw: &'a str,
+    pub(crate) name: &'a str,
+    /// Whether the tag ends with "/>".
+    pub(crate) self_closing: bool,
+}
+
+impl<'a> Tag<'a> {
+    /// The attributes of the tag: their names, and their undecoded values (without quotes)
+    /// with their byte ranges in `raw`.
+    pub(crate) fn attributes(&self) -> Vec<Attribute<'a>> {
+        let raw = self.raw;
+        let mut attributes = Vec::new();
+        let mut pos = 1 + self.name.len();
+        loop {
+            pos += count_whitespace(&raw[pos..]);
+            let name_len = raw[pos..]
+                .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+                .unwrap_or(raw.len() - pos);
+            if name_len == 0 {
+                if raw[pos..].starts_with('/') && !raw[pos..].starts_with("/>") {
+                    // a stray "/" in HTML
+                    pos += 1;
+                    continue;
+                }
+                return attributes;
+            }
+            let name = &raw[pos..pos + name_len];
+            pos += name_len;
+            let after_name = pos;
+            pos += count_whitespace(&raw[pos..]);
+            if !raw[pos..].starts_with('=') {
+                pos = after_name;
+                attributes.push(Attribute {
+                    name,
+                    value: None,
+                    value_start: after_name,
+                });
+                continue;
+            }
+            pos += 1;
+            pos += count_whitespace(&raw[pos..]);
+            let (value_start, value_end) = match raw[pos..].chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let len = raw[pos + 1..].find(quote).unwrap_or(raw.len() - pos - 1);
+                    (pos + 1, (pos + 1 + len).min(raw.len()))
+                }
+                _ => {
+                    let len = raw[pos..]
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(raw.len() - pos);
+                    (pos, pos + len)
+                }
+            };
+            attributes.push(Attribute {
+                name,
+                value: Some(&raw[value_start..value_end]),
+                value_start,
+            });
+            pos = value_end;
+            if raw[pos..].starts_with(['"', '\'']) {
+                pos += 1;
+            }
+        }
+    }
+
+    /// The undecoded value of the attribute `name`, if the tag has one.
+    pub(crate) fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes()
+            .into_iter()
+            .find(|attribute| attribute.name == name)
+            .and_then(|attribute| attribute.value)
+    }
+}
+
+/// An attribute of a [`Tag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Attribute<'a> {
+    pub(crate) name: &'a str,
+    /// The undecoded value, without quotes, or `None` for attributes without a value.
+    pub(crate) value: Option<&'a str>,
+    /// The byte index of the value in [`Tag::raw`].
+    pub(crate) value_start: usize,
+}
+
+/// An iterator over the [`Node`]s of a markup document.
+#[derive(Debug, Clone)]
+pub(crate) struct Scanner<'a> {
+    text: &'a str,
+    /// The byte index of the next node.
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        Scanner { text, pos: 0 }
+    }
+
+    /// Skip the content of the element `name`, such as an HTML `<script>`, which is not
+    /// markup, returning it. The next node is its end tag (if any).
+    pub(crate) fn raw_text(&mut self, name: &str) -> &'a str {
+        let rest = &self.text[self.pos..];
+        let mut search = 0;
+        let len = loop {
+            match rest[search..].find("</") {
+                Some(offset) => {
+                    let candidate = &rest[search + offset + 2..];
+                    if candidate
+                        .get(..name.len())
+                        .is_some_and(|candidate| candidate.eq_ignore_ascii_case(name))
+                        && candidate[name.len()..]
+                            .starts_with(|c: char| c.is_whitespace() || c == '>')
+                    {
+                        break search + offset;
+                    }
+                    search += offset + 2;
+                }
+                None => break rest.len(),
+            }
+        };
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// A syntax error at byte `pos`.
+    pub(crate) fn error_at(&self, pos: usize, message: &str) -> PigLatinError {
+        PigLatinError::Syntax {
+            line: self.text[..pos].matches('\n').count() + 1,
+            message: message.to_string(),
+        }
+    }
+
+    /// The byte index of the next node.
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a> Iterator for Scanner<'a> {
+    type Item = Result<Node<'a>, PigLatinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.pos;
+        let rest = &self.text[start..];
+        if rest.is_empty() {
+            return None;
+        }
+        if !starts_with_markup(rest) {
+            // "<" that does not start markup is text, as in HTML
+            let len = rest[1..]
+                .match_indices('<')
+                .map(|(idx, _)| idx + 1)
+                .find(|&idx| starts_with_markup(&rest[idx..]))
+                .unwrap_or(rest.len());
+            self.pos += len;
+            return Some(Ok(Node::Text(&rest[..len])));
+        }
+        for (open, close, what) in [
+            ("<!--", "-->", "comment"),
+            ("<![CDATA[", "]]>", "CDATA section"),
+            ("<?", "?>", "processing instruction"),
+            ("<!", ">", "declaration"),
+        ] {
+            if let Some(content) = rest.strip_prefix(open) {
+                let Some(len) = content.find(close) else {
+                    self.pos = self.text.len();
+                    return Some(Err(self.error_at(start, &format!("unterminated {what}"))));
+                };
+                let len = open.len() + len + close.len();
+                self.pos += len;
+                return Some(Ok(Node::Other(&rest[..len])));
+            }
+        }
+        let Some(len) = tag_len(rest) else {
+            self.pos = self.text.len();
+            return Some(Err(self.error_at(start, "unterminated tag")));
+        };
+        self.pos += len;
+        let raw = &rest[..len];
+        let is_end = raw.starts_with("</");
+        let name_start = if is_end { 2 } else { 1 };
+        let name_len = raw[name_start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '>' | '/'))
+            .unwrap_or(0);
+        let tag = Tag {
+            raw,
+            name: &raw[name_start..name_start + name_len],
+            self_closing: raw.ends_with("/>"),
+        };
+        Some(Ok(if is_end {
+            Node::EndTag(tag)
+        } else {
+            Node::StartTag(tag)
+        }))
+    }
+}
+
+/// Return `true` if `text` starts with a tag, comment or declaration.
+fn starts_with_markup(text: &str) -> bool {
+    let Some(rest) = text.strip_prefix('<') else {
+        return false;
+    };
+    let name = rest.strip_prefix('/').unwrap_or(rest);
+    rest.starts_with(['!', '?']) || name.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+/// Return the length of the tag `text` starts with, skipping ">" in quoted attributes.
+fn tag_len(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (idx, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(idx + 1),
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+fn count_whitespace(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+/// Decode the predefined and numeric character references of XML, such as "&amp;" or
+/// "&#233;". Other references are kept as-is.
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = rest.find('&') {
+        decoded.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        let decoded_char = rest[1..].find(';').and_then(|len| {
+            let c = match &rest[1..1 + len] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                name => {
+                    let number = name.strip_prefix('#')?;
+                    let code_point = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => number.parse().ok()?,
+                    };
+                    char::from_u32(code_point)?
+                }
+            };
+            Some((c, len + 2))
+        });
+        match decoded_char {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Translate `text` with `translate`, keeping the spelling of its character references
+/// (such as "&amp;" or "&#233;").
+///
+/// References are decoded for translation, so that "&#233;t&#233;" or "don&rsquo;t" is
+/// translated as one word, and each character of the translation that came from a
+/// reference is written as that reference again. References to apostrophes are
+/// translated as "’". References to other ASCII punctuation, which is never part of a
+/// word, are copied without translating them. Unknown references (such as "&bogus;"),
+/// like any "&" not starting a reference, are translated as plain text.
+pub(crate) fn translate_keeping_references(
+    text: &str,
+    output: &mut String,
+    mut translate: impl FnMut(&str, &mut String),
+) {
+    let mut decoded = String::new();
+    // the offsets in `decoded` of the characters decoded from references, and the references
+    let mut references = Vec::new();
+    let mut rest = text;
+    while let Some(idx) = rest.find('&') {
+        decoded.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        let Some((c, len)) =
+            reference_len(rest).and_then(|len| Some((decode_reference(&rest[..len])?, len)))
+        else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let reference = &rest[..len];
+        if c == '\'' || !c.is_ascii_punctuation() {
+            references.push((decoded.len(), reference));
+            decoded.push(if c == '\'' { '\u{2019}' } else { c });
+        } else {
+            translate_decoded(&decoded, &references, &mut translate, output);
+            decoded.clear();
+            references.clear();
+            output.push_str(reference);
+        }
+        rest = &rest[len..];
+    }
+    decoded.push_str(rest);
+    translate_decoded(&decoded, &references, &mut translate, output);
+}
+
+/// Translate `text` with `translate`, writing the characters decoded from `references`
+/// as those references again.
+///
+/// Translation keeps the order of the occurrences of each character (apart from those
+/// moved within a word), so the k-th occurrence of a character in the translation is
+/// spelled like its k-th occurrence in `text`.
+fn translate_decoded(
+    text: &str,
+    references: &[(usize, &str)],
+    translate: &mut impl FnMut(&str, &mut String),
+    output: &mut String,
+) {
+    if references.is_empty() {
+        return translate(text, output);
+    }
+    let mut spellings: HashMap<char, VecDeque<&str>> = references
+        .iter()
+        .filter_map(|&(idx, _)| text[idx..].chars().next())
+        .map(|c| (c, VecDeque::new()))
+        .collect();
+    let mut references = references.iter().peekable();
+    for (idx, c) in text.char_indices() {
+        if let Some(occurrences) = spellings.get_mut(&c) {
+            let spelling = match references.next_if(|&&(offset, _)| offset == idx) {
+                Some(&(_, reference)) => reference,
+                None => &text[idx..idx + c.len_utf8()],
+            };
+            occurrences.push_back(spelling);
+        }
+    }
+    let mut translated = String::with_capacity(text.len() * 2);
+    translate(text, &mut translated);
+    for c in translated.chars() {
+        match spellings.get_mut(&c).and_then(VecDeque::pop_front) {
+            Some(spelling) => output.push_str(spelling),
+            None => output.push(c),
+        }
+    }
+}
+
+/// Decode the numeric or named character reference `reference`, such as "&#233;" or
+/// "&eacute;", if it is known.
+fn decode_reference(reference: &str) -> Option<char> {
+    let name = reference.strip_prefix('&')?.strip_suffix(';')?;
+    let Some(number) = name.strip_prefix('#') else {
+        return named_reference(name);
+    };
+    let code_point = match number.strip_prefix(['x', 'X']) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => number.parse().ok()?,
+    };
+    char::from_u32(code_point)
+}
+
+/// Return the length of the character reference `text` starts with, if any, such as
+/// "&nbsp;" or "&#233;". References longer than 32 characters are not recognised.
+pub(crate) fn reference_len(text: &str) -> Option<usize> {
+    let len = text.strip_prefix('&')?.find(';')?;
+    if len > 32 {
+        return None;
+    }
+    let name = &text[1..1 + len];
+    let is_reference = match name.strip_prefix('#') {
+        Some(number) => match number.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => {
+            name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && name.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+    };
+    is_reference.then_some(len + 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    fn tag(raw: &'static str, name: &'static str) -> Tag<'static> {
+        Tag {
+            raw,
+            name,
+            self_closing: raw.ends_with("/>"),
+        }
+    }
+
+    #[test]
+    fn scan() {
+        let text = "<?xml version=\"1.0\"?>\n<a x='1>2'>Hi <b>there</b><br/>a < b<!-- c --></a>";
+        let nodes: Vec<_> = Scanner::new(text).map(Result::unwrap).collect();
+        assert_eq!(
+            nodes,
+            [
+                Node::Other("<?xml version=\"1.0\"?>"),
+                Node::Text("\n"),
+                Node::StartTag(tag("<a x='1>2'>", "a")),
+                Node::Text("Hi "),
+                Node::StartTag(tag("<b>", "b")),
+                Node::Text("there"),
+                Node::EndTag(tag("</b>", "b")),
+                Node::StartTag(tag("<br/>", "br")),
+                Node::Text("a < b"),
+                Node::Other("<!-- c -->"),
+                Node::EndTag(tag("</a>", "a")),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        for (text, line) in [("a\n<b", 2), ("<!-- a\n", 1), ("<a>\n<![CDATA[ x", 2)] {
+            match Scanner::new(text).find_map(Result::err) {
+                Some(PigLatinError::Syntax {
+                    line: error_line, ..
+                }) => assert_eq!(error_line, line, "{text:?}"),
+                other => panic!("expected a syntax error for {text:?}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn raw_text() {
+        let mut scanner = Scanner::new("<script>if (a</b) {}</SCRIPT ><p>");
+        scanner.next();
+        assert_eq!(scanner.raw_text("script"), "if (a</b) {}");
+        assert_eq!(
+            scanner.next(),
+            Some(Ok(Node::EndTag(tag("</SCRIPT >", "SCRIPT"))))
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        let tag = tag(
+            "<input  disabled title=\"A &amp; B\" alt='x' data-n=5 / >",
+            "input",
+        );
+        let attributes: Vec<_> = tag
+            .attributes()
+            .into_iter()
+            .map(|attribute| (attribute.name, attribute.value))
+            .collect();
+        assert_eq!(
+            attributes,
+            [
+                ("disabled", None),
+                ("title", Some("A &amp; B")),
+                ("alt", Some("x")),
+                ("data-n", Some("5")),
+            ]
+        );
+        assert_eq!(tag.attribute("alt"), Some("x"));
+        let title = tag.attributes()[1];
+        assert_eq!(
+            &tag.raw[title.value_start..title.value_start + 9],
+            "A &amp; B"
+        );
+    }
+
+    #[test]
+    fn entities() {
+        let decoded = decode_entities("a &amp; b &lt;&#233;&#x41;&nbsp;& c &bogus");
+        assert_eq!(decoded, "a & b <éA&nbsp;& c &bogus");
+        let mut translated = String::new();
+        translate_keeping_references(
+            "a &amp;b&#8217;s &amp;amp; &bogus; &eacute;t&#xE9;",
+            &mut translated,
+            |piece, output| write!(output, "[{}]", piece.to_uppercase()).unwrap(),
+        );
+        assert_eq!(translated, "[A ]&amp;[B&#8217;S ]&amp;[AMP; &BOGUS; ÉTÉ]");
+    }
+}
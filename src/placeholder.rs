@@ -0,0 +1,468 @@
+//! Recognising format placeholders, such as `{name}`, `%s` or `<b>`, anywhere in text.
+
+use std::ops::Range;
+
+use crate::markup::reference_len;
+use crate::{ProtectedClass, Protection};
+
+/// Find the placeholders of all classes protected by `protection` in `text`.
+///
+/// Pushes the byte ranges of the placeholders onto `spans`, in order. For ICU
+/// plural and select arguments, only their structure is a placeholder: the
+/// message text of each case is not, so that it can be translated.
+pub(crate) fn placeholder_spans(
+    text: &str,
+    protection: &Protection,
+    spans: &mut Vec<Range<usize>>,
+) {
+    scan(text, 0..text.len(), protection, false, spans);
+}
+
+/// Implement [`placeholder_spans`] for `text[range]`.
+///
+/// If `in_plural`, this is the message text of an ICU plural case, where "#" is
+/// a placeholder for the number.
+fn scan(
+    text: &str,
+    range: Range<usize>,
+    protection: &Protection,
+    in_plural: bool,
+    spans: &mut Vec<Range<usize>>,
+) {
+    let mut pos = range.start;
+    while let Some(offset) = text[pos..range.end].find(['%', '{', '<', '&', '#']) {
+        let start = pos + offset;
+        let rest = &text[start..range.end];
+        let end = match rest.as_bytes()[0] {
+            b'#' if in_plural && protection.protects(ProtectedClass::Icu) => Some(start + 1),
+            b'%' if protection.protects(ProtectedClass::Printf) => {
+                printf_len(rest).map(|len| start + len)
+            }
+            b'{' => {
+                let mustache = protection
+                    .protects(ProtectedClass::Mustache)
+                    .then(|| mustache_len(rest))
+                    .flatten();
+                let python = || {
+                    protection
+                        .protects(ProtectedClass::PythonFormat)
+                        .then(|| python_format_len(rest))
+                        .flatten()
+                };
+                match mustache {
+                    Some(len) => Some(start + len),
+                    None if protection.protects(ProtectedClass::Icu) => {
+                        match icu_argument(text, start, range.end, protection, spans) {
+                            Some(end) => {
+                                // the spans are already pushed
+                                pos = end;
+                                continue;
+                            }
+                            None => python().map(|len| start + len),
+                        }
+                    }
+                    None => python().map(|len| start + len),
+                }
+            }
+            b'<' | b'&' if protection.protects(ProtectedClass::HtmlTag) => {
+                html_len(rest).map(|len| start + len)
+            }
+            _ => None,
+        };
+        match end {
+            Some(end) => {
+                spans.push(start..end);
+                pos = end;
+            }
+            // all candidates are single bytes
+            None => pos = start + 1,
+        }
+    }
+}
+
+/// Return the length of the printf-style placeholder `text` starts with, if any,
+/// e.g. "%s", "%1$d", "%-5.2f", "%(name)s", "%@", "%#@pigs@" or "%%".
+fn printf_len(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut idx = 1;
+    if bytes.get(idx) == Some(&b'%') {
+        return Some(2);
+    }
+    if let Some(variable) = text[idx..].strip_prefix("#@") {
+        // the variables of Apple's .stringsdict files
+        let name_len = variable
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(variable.len());
+        if name_len > 0 && variable[name_len..].starts_with('@') {
+            return Some(idx + 2 + name_len + 1);
+        }
+    }
+    if bytes.get(idx) == Some(&b'(') {
+        // Python's "%(name)s"
+        let name_len = text[idx + 1..].find(')')?;
+        let name = &text[idx + 1..idx + 1 + name_len];
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        idx += name_len + 2;
+    } else {
+        // positional "%1$d"
+        let digits = count_while(&bytes[idx..], |b| b.is_ascii_digit());
+        if digits > 0 && bytes.get(idx + digits) == Some(&b'$') {
+            idx += digits + 1;
+        }
+    }
+    // flags (without " ", to not mistake "50% off" for a placeholder)
+    idx += count_while(&bytes[idx..], |b| {
+        matches!(b, b'-' | b'+' | b'#' | b'0' | b'\'')
+    });
+    // width
+    idx += match bytes.get(idx) {
+        Some(b'*') => 1,
+        _ => count_while(&bytes[idx..], |b| b.is_ascii_digit()),
+    };
+    // precision
+    if bytes.get(idx) == Some(&b'.') {
+        idx += 1;
+        let precision = match bytes.get(idx) {
+            Some(b'*') => 1,
+            _ => count_while(&bytes[idx..], |b| b.is_ascii_digit()),
+        };
+        if precision == 0 {
+            return None;
+        }
+        idx += precision;
+    }
+    // length modifier
+    for modifier in ["hh", "ll", "h", "l", "L", "q", "j", "z", "t"] {
+        if text[idx..].starts_with(modifier) {
+            idx += modifier.len();
+            break;
+        }
+    }
+    match bytes.get(idx) {
+        Some(b'd' | b'i' | b'o' | b'u' | b'x' | b'X' | b'e' | b'E' | b'f' | b'F' | b'g' | b'G')
+        | Some(b'a' | b'A' | b'c' | b's' | b'p' | b'n' | b'@') => Some(idx + 1),
+        _ => None,
+    }
+}
+
+fn count_while(bytes: &[u8], predicate: impl Fn(u8) -> bool) -> usize {
+    bytes.iter().take_while(|&&b| predicate(b)).count()
+}
+
+/// Return the length of the Mustache tag `text` starts with, if any, e.g.
+/// "{{count}}", "{{#items}}" or "{{{html}}}".
+fn mustache_len(text: &str) -> Option<usize> {
+    let (open, close) = if text.starts_with("{{{") {
+        ("{{{", "}}}")
+    } else if text.starts_with("{{") {
+        ("{{", "}}")
+    } else {
+        return None;
+    };
+    let content_len = text[open.len()..].find(close)?;
+    let content = &text[open.len()..open.len() + content_len];
+    if content.trim().is_empty() || content.contains(['{', '}']) {
+        return None;
+    }
+    Some(open.len() + content_len + close.len())
+}
+
+/// Return the length of the Python format field `text` starts with, if any, e.g.
+/// "{}", "{0}", "{name}", "{user.name!r}" or "{price:>10.2f}".
+fn python_format_len(text: &str) -> Option<usize> {
+    let close = text.find('}')?;
+    let field = &text[1..close];
+    let (field, spec) = match field.split_once(':') {
+        Some((field, spec)) => (field, Some(spec)),
+        None => (field, None),
+    };
+    let (name, conversion) = match field.split_once('!') {
+        Some((name, conversion)) => (name, Some(conversion)),
+        None => (field, None),
+    };
+    let is_name = name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '[' | ']'));
+    let is_conversion = conversion.is_none_or(|conversion| matches!(conversion, "r" | "s" | "a"));
+    let is_spec = spec.is_none_or(|spec| !spec.contains(['{', '\n']));
+    (is_name && is_conversion && is_spec).then_some(close + 1)
+}
+
+/// Parse the ICU message format argument starting at `start` (a "{"), e.g. "{name}",
+/// "{count, number}" or "{count, plural, one {# pig} other {# pigs}}".
+///
+/// Returns the end of the argument, and pushes its spans onto `spans`, if there is one.
+fn icu_argument(
+    text: &str,
+    start: usize,
+    end: usize,
+    protection: &Protection,
+    spans: &mut Vec<Range<usize>>,
+) -> Option<usize> {
+    let mut parser = Parser {
+        text: &text[..end],
+        pos: start + 1,
+    };
+    parser.skip_whitespace();
+    parser.identifier(|c| c.is_alphanumeric() || c == '_')?;
+    parser.skip_whitespace();
+    if parser.eat('}') {
+        spans.push(start..parser.pos);
+        return Some(parser.pos);
+    }
+    if !parser.eat(',') {
+        return None;
+    }
+    parser.skip_whitespace();
+    let argument_type = parser.identifier(|c| c.is_ascii_alphabetic())?;
+    parser.skip_whitespace();
+    if parser.eat('}') {
+        spans.push(start..parser.pos);
+        return Some(parser.pos);
+    }
+    if !parser.eat(',') {
+        return None;
+    }
+    if !matches!(argument_type, "plural" | "selectordinal" | "select") {
+        // a style, such as "{amount, number, ::currency/EUR}"
+        let close = matching_brace(parser.text, start)?;
+        spans.push(start..close + 1);
+        return Some(close + 1);
+    }
+
+    // the cases, e.g. "=0 {no pigs} one {# pig} other {# pigs}"
+    let in_plural = argument_type != "select";
+    let mut case_spans = Vec::new();
+    let mut structure_start = start;
+    loop {
+        parser.skip_whitespace();
+        if parser.eat('}') {
+            case_spans.push(structure_start..parser.pos);
+            spans.append(&mut case_spans);
+            return Some(parser.pos);
+        }
+        let selector = parser.identifier(|c| !c.is_whitespace() && c != '{' && c != '}')?;
+        if selector.starts_with("offset:") {
+            continue;
+        }
+        parser.skip_whitespace();
+        let open = parser.pos;
+        if !parser.eat('{') {
+            return None;
+        }
+        let close = matching_brace(parser.text, open)?;
+        case_spans.push(structure_start..open + 1);
+        scan(
+            text,
+            open + 1..close,
+            protection,
+            in_plural,
+            &mut case_spans,
+        );
+        structure_start = close;
+        parser.pos = close + 1;
+    }
+}
+
+/// Find the "}" matching the "{" at `open`.
+fn matching_brace(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (idx, c) in text[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A minimal cursor for parsing ICU arguments.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let is_next = self.text[self.pos..].starts_with(expected);
+        if is_next {
+            self.pos += expected.len_utf8();
+        }
+        is_next
+    }
+
+    /// Consume a non-empty run of `char`s matching `predicate`.
+    fn identifier(&mut self, predicate: impl Fn(char) -> bool) -> Option<&'a str> {
+        let rest = &self.text[self.pos..];
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+}
+
+/// Return the length of the HTML tag, comment or character reference `text` starts
+/// with, if any, e.g. "<b>", "</a>", "<br/>", "<a href=\"#\">", "<!-- note -->" or "&amp;".
+fn html_len(text: &str) -> Option<usize> {
+    if text.starts_with('&') {
+        return reference_len(text);
+    }
+    if let Some(comment) = text.strip_prefix("<!--") {
+        return comment.find("-->").map(|len| len + 7);
+    }
+    let name = text[1..].strip_prefix('/').unwrap_or(&text[1..]);
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut quote = None;
+    for (idx, c) in text.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(idx + 1),
+            (None, '<') => return None,
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str, class: ProtectedClass) -> Vec<&str> {
+        let mut spans = Vec::new();
+        placeholder_spans(text, &Protection::none().with(class), &mut spans);
+        spans.into_iter().map(|span| &text[span]).collect()
+    }
+
+    #[test]
+    fn printf() {
+        assert_eq!(
+            placeholders(
+                "%s has %d pigs (%1$d, %-5.2f, %(name)s, %@, %lld, %*d) at 100%% or 50% off!",
+                ProtectedClass::Printf
+            ),
+            ["%s", "%d", "%1$d", "%-5.2f", "%(name)s", "%@", "%lld", "%*d", "%%"]
+        );
+        assert_eq!(
+            placeholders("You have %#@pigs@ and %#@ bad@", ProtectedClass::Printf),
+            ["%#@pigs@", "%#@"]
+        );
+        assert_eq!(
+            placeholders("100%. 5 % s %", ProtectedClass::Printf),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn python_format() {
+        assert_eq!(
+            placeholders(
+                "{} {0} {name} {user.name!r} {price:>10.2f} {items[0]} { not one } {a b}",
+                ProtectedClass::PythonFormat
+            ),
+            [
+                "{}",
+                "{0}",
+                "{name}",
+                "{user.name!r}",
+                "{price:>10.2f}",
+                "{items[0]}"
+            ]
+        );
+    }
+
+    #[test]
+    fn mustache() {
+        assert_eq!(
+            placeholders(
+                "{{count}} {{#items}}{{name}}{{/items}} {{{html}}} {{ }}",
+                ProtectedClass::Mustache
+            ),
+            [
+                "{{count}}",
+                "{{#items}}",
+                "{{name}}",
+                "{{/items}}",
+                "{{{html}}}"
+            ]
+        );
+    }
+
+    #[test]
+    fn icu() {
+        assert_eq!(
+            placeholders(
+                "{name} has {count, number} pigs since {date, date, ::yMMMd}.",
+                ProtectedClass::Icu
+            ),
+            ["{name}", "{count, number}", "{date, date, ::yMMMd}"]
+        );
+        assert_eq!(
+            placeholders(
+                "{count, plural, offset:1 =0 {no pigs} one {# pig and {name}} other {# pigs}}!",
+                ProtectedClass::Icu
+            ),
+            [
+                "{count, plural, offset:1 =0 {",
+                "} one {",
+                "#",
+                "{name}",
+                "} other {",
+                "#",
+                "}}"
+            ]
+        );
+        assert_eq!(
+            placeholders(
+                "{gender, select, female {She} other {They #}}",
+                ProtectedClass::Icu
+            ),
+            ["{gender, select, female {", "} other {", "}}"]
+        );
+        // not ICU
+        assert_eq!(
+            placeholders("{} { } {a b} {count, plural, one}", ProtectedClass::Icu),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn html() {
+        assert_eq!(
+            placeholders(
+                "<b>Bold</b>, <a href=\"x>y\" title='z'>link</a><br/> &amp; &#39; &#x1F437; <!-- note --> a < b & c",
+                ProtectedClass::HtmlTag
+            ),
+            [
+                "<b>",
+                "</b>",
+                "<a href=\"x>y\" title='z'>",
+                "</a>",
+                "<br/>",
+                "&amp;",
+                "&#39;",
+                "&#x1F437;",
+                "<!-- note -->"
+            ]
+        );
+    }
+}
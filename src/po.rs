@@ -0,0 +1,431 @@
+//! Translating gettext catalogs (`.po` and `.pot` files).
+
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::{PigLatinError, Translator};
+
+/// # Translate a gettext catalog (`.po` or `.pot` file) into Pig-Latin.
+///
+/// Returns the catalog with every `msgstr` (including the plural forms `msgstr[n]`)
+/// set to the translation of its `msgid`, or of its `msgid_plural` for all but the
+/// first plural form. Everything else (comments, references, flags, contexts, obsolete
+/// entries and the layout of the file) is kept as-is.
+///
+/// The header entry (with an empty `msgid`) is not translated. Its template placeholders
+/// for the charset and the plural forms, as found in `.pot` files, are filled in
+/// with "UTF-8" and the plural forms of English, so that the result is a valid `.po` file.
+///
+/// Format placeholders in messages are only kept intact if `translator` protects them,
+/// see [`Protection::with_placeholders`](crate::Protection::with_placeholders).
+///
+/// Returns [`PigLatinError::Syntax`] if `catalog` is not a well-formed catalog.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_po, Protection, Translator};
+/// let catalog = r#"#: src/main.c:42
+/// #, c-format
+/// msgid "Hello, %s!"
+/// msgstr ""
+///
+/// msgctxt "menu"
+/// msgid "One pig"
+/// msgid_plural "%d pigs"
+/// msgstr[0] ""
+/// msgstr[1] ""
+/// "#;
+/// let mut translator =
+///     Translator::default().with_protection(Protection::default().with_placeholders());
+/// assert_eq!(
+///     translate_po(catalog, &mut translator).unwrap(),
+///     r#"#: src/main.c:42
+/// #, c-format
+/// msgid "Hello, %s!"
+/// msgstr "Ellohay, %s!"
+///
+/// msgctxt "menu"
+/// msgid "One pig"
+/// msgid_plural "%d pigs"
+/// msgstr[0] "Onehay igpay"
+/// msgstr[1] "%d igspay"
+/// "#
+/// );
+/// ```
+pub fn translate_po(catalog: &str, translator: &mut Translator) -> Result<String, PigLatinError> {
+    let mut translated = String::with_capacity(catalog.len() * 2);
+    let mut entry = Entry::default();
+    let mut field = Field::None;
+    for (line_idx, line) in catalog.split_inclusive('\n').enumerate() {
+        let syntax_error = |message: &str| PigLatinError::Syntax {
+            line: line_idx + 1,
+            message: message.to_string(),
+        };
+        let content = line.trim_end_matches(['\n', '\r']);
+        let line_ending = &line[content.len()..];
+        let trimmed = content.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            // comments, and blank lines, end the strings of an entry
+            if field == Field::Msgstr {
+                entry = Entry::default();
+            }
+            field = Field::None;
+            translated.push_str(line);
+            continue;
+        }
+
+        if trimmed.starts_with('"') {
+            let value = unescape(trimmed).ok_or_else(|| syntax_error("malformed string"))?;
+            match field {
+                Field::None => return Err(syntax_error("string without a keyword")),
+                Field::Msgctxt => entry.has_msgctxt = true,
+                Field::Msgid => {
+                    entry.msgid.push_str(&value);
+                    entry.msgid_lines += 1;
+                }
+                Field::MsgidPlural => {
+                    entry.msgid_plural.push_str(&value);
+                    entry.msgid_plural_lines += 1;
+                }
+                // replaced by the translation
+                Field::Msgstr if !entry.is_header() => continue,
+                Field::Msgstr => {}
+            }
+            if field == Field::Msgstr {
+                translated.push_str(&fill_header_template(content));
+                translated.push_str(line_ending);
+            } else {
+                translated.push_str(line);
+            }
+            continue;
+        }
+
+        let (keyword, string) = trimmed
+            .split_once(|c: char| c.is_whitespace())
+            .map(|(keyword, string)| (keyword, string.trim_start()))
+            .ok_or_else(|| syntax_error("expected a keyword and a string"))?;
+        let value = unescape(string).ok_or_else(|| syntax_error("malformed string"))?;
+        if field == Field::Msgstr && !keyword.starts_with("msgstr") {
+            // a new entry, without a blank line in-between
+            entry = Entry::default();
+        }
+        match keyword {
+            "msgctxt" => {
+                entry.has_msgctxt = true;
+                field = Field::Msgctxt;
+            }
+            "msgid" => {
+                entry.msgid = value;
+                entry.msgid_lines = 1;
+                field = Field::Msgid;
+            }
+            "msgid_plural" => {
+                entry.msgid_plural = value;
+                entry.msgid_plural_lines = 1;
+                field = Field::MsgidPlural;
+            }
+            _ if keyword.starts_with("msgstr") => {
+                let plural_idx = match &keyword["msgstr".len()..] {
+                    "" => 0,
+                    idx => idx
+                        .strip_prefix('[')
+                        .and_then(|idx| idx.strip_suffix(']'))
+                        .and_then(|idx| idx.parse::<usize>().ok())
+                        .ok_or_else(|| syntax_error("malformed plural index"))?,
+                };
+                field = Field::Msgstr;
+                if entry.is_header() {
+                    translated.push_str(&fill_header_template(content));
+                    translated.push_str(line_ending);
+                } else {
+                    let (source, source_lines) = if plural_idx == 0 {
+                        (&entry.msgid, entry.msgid_lines)
+                    } else {
+                        (&entry.msgid_plural, entry.msgid_plural_lines)
+                    };
+                    write_string(
+                        &mut translated,
+                        keyword,
+                        &translator.translate(source),
+                        source_lines > 1,
+                        line_ending,
+                    );
+                }
+                continue;
+            }
+            _ => return Err(syntax_error(&format!("unknown keyword {keyword:?}"))),
+        }
+        translated.push_str(line);
+    }
+    Ok(translated)
+}
+
+/// The strings of the entry being read.
+#[derive(Debug, Default)]
+struct Entry {
+    has_msgctxt: bool,
+    msgid: String,
+    msgid_lines: usize,
+    msgid_plural: String,
+    msgid_plural_lines: usize,
+}
+
+impl Entry {
+    /// Return `true` for the header entry, which has an empty `msgid` (and no context).
+    fn is_header(&self) -> bool {
+        self.msgid_lines > 0 && self.msgid.is_empty() && !self.has_msgctxt
+    }
+}
+
+/// The keyword the string(s) on the current line belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    None,
+    Msgctxt,
+    Msgid,
+    MsgidPlural,
+    Msgstr,
+}
+
+/// Replace the template placeholders of `.pot` headers.
+fn fill_header_template(line: &str) -> String {
+    line.replace("charset=CHARSET", "charset=UTF-8").replace(
+        "nplurals=INTEGER; plural=EXPRESSION;",
+        "nplurals=2; plural=(n != 1);",
+    )
+}
+
+/// Write `keyword` and the quoted `value`; if `multi_line`, as an empty string
+/// followed by one line per line of `value`, like gettext does.
+fn write_string(
+    output: &mut String,
+    keyword: &str,
+    value: &str,
+    multi_line: bool,
+    line_ending: &str,
+) {
+    output.push_str(keyword);
+    output.push(' ');
+    if !multi_line {
+        push_quoted(output, value);
+        output.push_str(line_ending);
+        return;
+    }
+    output.push_str("\"\"");
+    output.push_str(line_ending);
+    for line in value.split_inclusive('\n') {
+        push_quoted(output, line);
+        output.push_str(line_ending);
+    }
+}
+
+/// Push `value` as a C string literal.
+fn push_quoted(output: &mut String, value: &str) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            '\u{7}' => output.push_str("\\a"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            '\u{b}' => output.push_str("\\v"),
+            c if c.is_ascii_control() => {
+                write!(output, "\\{:03o}", c as u32).expect("writing to a String should not fail")
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+/// Parse a C string literal, such as `"Hello\n"`, returning `None` if it is malformed
+/// (including unknown escapes, and escaped bytes that are not UTF-8).
+fn unescape(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    // numeric escapes are bytes, which may be part of a multi-byte character
+    let mut value = Vec::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        let byte = match c {
+            '\\' => match chars.next()? {
+                'n' => b'\n',
+                't' => b'\t',
+                'r' => b'\r',
+                'a' => 0x7,
+                'b' => 0x8,
+                'f' => 0xc,
+                'v' => 0xb,
+                'x' => escaped_byte(&mut chars, 16, None, 2)?,
+                digit @ '0'..='7' => escaped_byte(&mut chars, 8, digit.to_digit(8), 2)?,
+                escaped @ ('"' | '\\' | '\'' | '?') => escaped as u8,
+                _ => return None,
+            },
+            // unescaped quotes end the string
+            '"' => return None,
+            c => {
+                value.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                continue;
+            }
+        };
+        value.push(byte);
+    }
+    String::from_utf8(value).ok()
+}
+
+/// Parse up to `max_digits` digits of a numeric escape, after the `value` of any
+/// digits already parsed. Returns `None` if there are no digits at all, or if the
+/// value does not fit into a byte.
+fn escaped_byte(
+    chars: &mut Peekable<Chars>,
+    radix: u32,
+    mut value: Option<u32>,
+    max_digits: usize,
+) -> Option<u8> {
+    for _ in 0..max_digits {
+        let Some(digit) = chars.peek().and_then(|c| c.to_digit(radix)) else {
+            break;
+        };
+        chars.next();
+        value = Some(value.unwrap_or(0) * radix + digit);
+    }
+    u8::try_from(value?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Protection;
+
+    fn translate(catalog: &str) -> Result<String, PigLatinError> {
+        let protection = Protection::default().with_placeholders();
+        translate_po(
+            catalog,
+            &mut Translator::default().with_protection(protection),
+        )
+    }
+
+    #[test]
+    fn pot_header() {
+        let catalog = r#"# SOME DESCRIPTIVE TITLE.
+#, fuzzy
+msgid ""
+msgstr ""
+"Project-Id-Version: pigs 1.0\n"
+"Content-Type: text/plain; charset=CHARSET\n"
+"Plural-Forms: nplurals=INTEGER; plural=EXPRESSION;\n"
+
+msgid "Pig"
+msgstr ""
+"#;
+        assert_eq!(
+            translate(catalog).unwrap(),
+            r#"# SOME DESCRIPTIVE TITLE.
+#, fuzzy
+msgid ""
+msgstr ""
+"Project-Id-Version: pigs 1.0\n"
+"Content-Type: text/plain; charset=UTF-8\n"
+"Plural-Forms: nplurals=2; plural=(n != 1);\n"
+
+msgid "Pig"
+msgstr "Igpay"
+"#
+        );
+    }
+
+    #[test]
+    fn multi_line_and_escapes() {
+        let catalog =
+            "msgid \"\"\n\"Say \\\"oink\\\"\\n\"\n\"twice.\"\nmsgstr \"old\"\n\"translation\"\n";
+        assert_eq!(
+            translate(catalog).unwrap(),
+            "msgid \"\"\n\"Say \\\"oink\\\"\\n\"\n\"twice.\"\nmsgstr \"\"\n\"Aysay \\\"oinkhay\\\"\\n\"\n\"icetway.\"\n"
+        );
+    }
+
+    #[test]
+    fn numeric_escapes() {
+        let catalog = "msgid \"\\x50ig \\120ig\\0\\x1b caf\\xc3\\xa9\"\nmsgstr \"\"\n";
+        assert_eq!(
+            translate(catalog).unwrap(),
+            "msgid \"\\x50ig \\120ig\\0\\x1b caf\\xc3\\xa9\"\nmsgstr \"Igpay Igpay\\000\\033 afécay\"\n"
+        );
+        for catalog in [
+            "msgid \"\\q\"\n",
+            "msgid \"\\x\"\n",
+            "msgid \"\\777\"\n",
+            "msgid \"\\xff\"\n",
+        ] {
+            assert!(matches!(
+                translate(catalog),
+                Err(PigLatinError::Syntax { line: 1, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn plural_forms_and_context() {
+        let catalog = "msgctxt \"farm\"\nmsgid \"{n} pig\"\nmsgid_plural \"{n} pigs\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\nmsgstr[2] \"\"\n";
+        assert_eq!(
+            translate(catalog).unwrap(),
+            "msgctxt \"farm\"\nmsgid \"{n} pig\"\nmsgid_plural \"{n} pigs\"\nmsgstr[0] \"{n} igpay\"\nmsgstr[1] \"{n} igspay\"\nmsgstr[2] \"{n} igspay\"\n"
+        );
+    }
+
+    #[test]
+    fn context_with_empty_msgid_is_not_header() {
+        let catalog = "msgctxt \"x\"\nmsgid \"\"\nmsgstr \"kept?\"\n";
+        assert_eq!(
+            translate(catalog).unwrap(),
+            "msgctxt \"x\"\nmsgid \"\"\nmsgstr \"\"\n"
+        );
+    }
+
+    #[test]
+    fn comments_and_obsolete_entries() {
+        let catalog = "# translator comment\n#. extracted\n#: file.c:1\n#| msgid \"Old\"\nmsgid \"New\"\nmsgstr \"\"\n\n#~ msgid \"Gone\"\n#~ msgstr \"Onegay\"\n";
+        assert_eq!(
+            translate(catalog).unwrap(),
+            "# translator comment\n#. extracted\n#: file.c:1\n#| msgid \"Old\"\nmsgid \"New\"\nmsgstr \"Ewnay\"\n\n#~ msgid \"Gone\"\n#~ msgstr \"Onegay\"\n"
+        );
+    }
+
+    #[test]
+    fn crlf_and_no_blank_lines() {
+        let catalog = "msgid \"a pig\"\r\nmsgstr \"\"\r\nmsgid \"Pigs\"\r\nmsgstr \"\"";
+        assert_eq!(
+            translate(catalog).unwrap(),
+            "msgid \"a pig\"\r\nmsgstr \"ahay igpay\"\r\nmsgid \"Pigs\"\r\nmsgstr \"Igspay\""
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        for (catalog, line) in [
+            ("msgid \"unterminated\nmsgstr \"\"\n", 1),
+            (
+                "msgid \"ok\"\nmsgstr \"\"\n\"continued\"\n\n\"orphan\"\n",
+                5,
+            ),
+            ("msgid \"ok\"\nmsgstr[x] \"\"\n", 2),
+            ("msgid \"ok\"\nmsgtext \"\"\n", 2),
+            ("msgid\n", 1),
+        ] {
+            match translate(catalog) {
+                Err(PigLatinError::Syntax {
+                    line: error_line, ..
+                }) => {
+                    assert_eq!(error_line, line, "{catalog:?}")
+                }
+                other => panic!("expected a syntax error for {catalog:?}, got {other:?}"),
+            }
+        }
+    }
+}
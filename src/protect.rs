@@ -0,0 +1,558 @@
+//! Recognising text that is not English, such as URLs, to keep it out of translation.
+
+use std::ops::Range;
+
+use std::iter::once;
+
+use crate::details::byte_offset;
+use crate::placeholder::placeholder_spans;
+
+/// A class of text that [`Protection`] can keep from being translated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ProtectedClass {
+    /// URLs with a scheme (`https://example.com/pig`), or starting with "www.".
+    Url,
+    /// Email addresses ("pig@example.com").
+    Email,
+    /// File paths that are absolute ("/usr/bin", "C:\Windows", "~/pig"), explicitly
+    /// relative ("./pig", "../pig"), or that end in a file name with an extension
+    /// ("src/lib.rs").
+    Path,
+    /// Decimal numbers ("42", "3.14", "10,000", "6.02e23") and hexadecimal numbers ("0xFF").
+    Number,
+    /// Version strings with at least two components ("1.2", "v2.0.1-beta+42").
+    Version,
+    /// UUIDs ("123e4567-e89b-12d3-a456-426614174000").
+    Uuid,
+    /// printf-style placeholders ("%s", "%1$d", "%.2f", "%(name)s", "%@").
+    Printf,
+    /// Python format fields ("{}", "{0}", "{name}", "{price:.2f}").
+    PythonFormat,
+    /// ICU message format arguments ("{name}", "{count, number}"). Of plural and
+    /// select arguments, only the structure is protected, whereas the message of each
+    /// case is translated ("{count, plural, one {# pig} other {# pigs}}" becomes
+    /// "{count, plural, one {# igpay} other {# igspay}}").
+    Icu,
+    /// Mustache and Handlebars tags ("{{count}}", "{{#items}}", "{{{html}}}").
+    Mustache,
+    /// HTML (or XML) tags, comments and character references (`<b>`, `</a>`,
+    /// `<br/>`, `&amp;`).
+    HtmlTag,
+}
+
+impl ProtectedClass {
+    /// The classes of placeholders, which are found anywhere in text.
+    const PLACEHOLDERS: [ProtectedClass; 5] = [
+        ProtectedClass::Printf,
+        ProtectedClass::PythonFormat,
+        ProtectedClass::Icu,
+        ProtectedClass::Mustache,
+        ProtectedClass::HtmlTag,
+    ];
+
+    /// The other classes, which are matched against whitespace-delimited text, in
+    /// the order in which they are tried.
+    const WHOLE: [ProtectedClass; 6] = [
+        ProtectedClass::Uuid,
+        ProtectedClass::Url,
+        ProtectedClass::Email,
+        ProtectedClass::Path,
+        ProtectedClass::Version,
+        ProtectedClass::Number,
+    ];
+
+    const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+
+    /// Return `true` if all of `text` belongs to this class (one of [`ProtectedClass::WHOLE`]).
+    fn recognizes(self, text: &str) -> bool {
+        match self {
+            ProtectedClass::Url => is_url(text),
+            ProtectedClass::Email => is_email(text),
+            ProtectedClass::Path => is_path(text),
+            ProtectedClass::Number => is_number(text),
+            ProtectedClass::Version => is_version(text),
+            ProtectedClass::Uuid => is_uuid(text),
+            ProtectedClass::Printf
+            | ProtectedClass::PythonFormat
+            | ProtectedClass::Icu
+            | ProtectedClass::Mustache
+            | ProtectedClass::HtmlTag => false,
+        }
+    }
+}
+
+/// # The classes of text that are copied into a translation verbatim.
+///
+/// Before translating words, [`translate`](crate::translate) looks for text that is not
+/// English, such as URLs or numbers, and copies it as-is. Each whitespace-delimited piece
+/// of text (without any surrounding punctuation, such as brackets, quotes, or a full stop)
+/// is checked against the enabled [`ProtectedClass`]es.
+///
+/// Placeholders of format strings, such as "{name}" or "%s", are protected anywhere
+/// in the text instead, see [`Protection::with_placeholders`].
+///
+/// The [`Default`] protects all classes but placeholders. Use a
+/// [`Translator`](crate::Translator) to change them.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate, ProtectedClass, Protection, Translator};
+/// assert_eq!(
+///     translate("See https://example.com/pig, page 5."),
+///     "Eesay https://example.com/pig, agepay 5."
+/// );
+///
+/// let protection = Protection::default().without(ProtectedClass::Number);
+/// let mut translator = Translator::default().with_protection(protection);
+/// assert_eq!(
+///     translator.translate("See https://example.com/pig, page 5."),
+///     "Eesay https://example.com/pig, agepay 5ay."
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Protection {
+    classes: u32,
+}
+
+impl Protection {
+    /// Protect nothing, translating all words.
+    pub const fn none() -> Self {
+        Protection { classes: 0 }
+    }
+
+    /// Also protect text of `class`.
+    pub const fn with(mut self, class: ProtectedClass) -> Self {
+        self.classes |= class.bit();
+        self
+    }
+
+    /// Stop protecting text of `class`.
+    pub const fn without(mut self, class: ProtectedClass) -> Self {
+        self.classes &= !class.bit();
+        self
+    }
+
+    /// Return `true` if text of `class` is protected.
+    pub const fn protects(&self, class: ProtectedClass) -> bool {
+        self.classes & class.bit() != 0
+    }
+
+    /// Also protect all classes of placeholders, as used for
+    /// [pseudo-localization](crate::PseudoLocalizer).
+    pub const fn with_placeholders(self) -> Self {
+        self.with(ProtectedClass::Printf)
+            .with(ProtectedClass::PythonFormat)
+            .with(ProtectedClass::Icu)
+            .with(ProtectedClass::Mustache)
+            .with(ProtectedClass::HtmlTag)
+    }
+
+    /// Return `true` if no class is protected.
+    pub const fn is_none(&self) -> bool {
+        self.classes == 0
+    }
+
+    /// Return `true` if all of `text` belongs to a protected class.
+    fn recognizes(&self, text: &str) -> bool {
+        ProtectedClass::WHOLE
+            .into_iter()
+            .any(|class| self.protects(class) && class.recognizes(text))
+    }
+
+    /// Find the byte ranges of all protected text in `text`, in order.
+    fn protected_spans(&self, text: &str) -> Vec<Range<usize>> {
+        let mut placeholders = Vec::new();
+        if ProtectedClass::PLACEHOLDERS
+            .into_iter()
+            .any(|class| self.protects(class))
+        {
+            placeholder_spans(text, self, &mut placeholders);
+        }
+        let mut spans = Vec::with_capacity(placeholders.len());
+        let mut gap_start = 0;
+        for placeholder in placeholders.into_iter().chain(once(text.len()..text.len())) {
+            let gap = &text[gap_start..placeholder.start];
+            spans.extend(
+                gap.split(char::is_whitespace)
+                    .filter_map(|chunk| self.protected_span(text, chunk)),
+            );
+            gap_start = placeholder.end;
+            if !placeholder.is_empty() {
+                spans.push(placeholder);
+            }
+        }
+        spans
+    }
+
+    /// Find the byte range of the protected text in a whitespace-free `chunk`, if any.
+    fn protected_span(&self, text: &str, chunk: &str) -> Option<Range<usize>> {
+        let core = trim_trailing_punctuation(chunk.trim_start_matches(is_leading_punctuation));
+        if core.is_empty() || !self.recognizes(core) {
+            return None;
+        }
+        let start = byte_offset(text, core);
+        Some(start..start + core.len())
+    }
+}
+
+impl Default for Protection {
+    /// Protect URLs, email addresses, file paths, numbers, version strings and UUIDs,
+    /// but no placeholders.
+    fn default() -> Self {
+        DEFAULT_PROTECTION
+    }
+}
+
+/// The default protection, shared by all functions not taking a [`Protection`].
+pub(crate) static DEFAULT_PROTECTION: Protection = Protection::none()
+    .with(ProtectedClass::Url)
+    .with(ProtectedClass::Email)
+    .with(ProtectedClass::Path)
+    .with(ProtectedClass::Number)
+    .with(ProtectedClass::Version)
+    .with(ProtectedClass::Uuid);
+
+/// Split `text` into alternating unprotected and protected substrings.
+///
+/// Yields each non-empty substring together with `true` if it is protected.
+/// Concatenating the yielded substrings reproduces `text` exactly.
+pub(crate) fn split_protected<'a>(
+    text: &'a str,
+    protection: &'a Protection,
+) -> impl Iterator<Item = (&'a str, bool)> + 'a {
+    let mut spans = if protection.is_none() {
+        Vec::new()
+    } else {
+        protection.protected_spans(text)
+    }
+    .into_iter();
+    let mut last_end = 0;
+    let mut pending: Option<Range<usize>> = None;
+    std::iter::from_fn(move || {
+        let span = match pending.take() {
+            Some(span) => span,
+            None => match spans.next() {
+                Some(span) if span.start > last_end => {
+                    let unprotected = &text[last_end..span.start];
+                    last_end = span.start;
+                    pending = Some(span);
+                    return Some((unprotected, false));
+                }
+                Some(span) => span,
+                None if last_end < text.len() => {
+                    let unprotected = &text[last_end..];
+                    last_end = text.len();
+                    return Some((unprotected, false));
+                }
+                None => return None,
+            },
+        };
+        last_end = span.end;
+        Some((&text[span], true))
+    })
+}
+
+fn is_leading_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '(' | '[' | '{' | '<' | '"' | '\'' | '“' | '‘' | '«' | '‹' | '¿' | '¡'
+    )
+}
+
+/// Strip punctuation from the end of `text`, keeping closing brackets that are
+/// opened within `text` (as in "https://en.wikipedia.org/wiki/Pig_(disambiguation)").
+fn trim_trailing_punctuation(mut text: &str) -> &str {
+    while let Some(last) = text.chars().next_back() {
+        let is_trailing = match last {
+            ')' => text.matches('(').count() < text.matches(')').count(),
+            ']' => text.matches('[').count() < text.matches(']').count(),
+            '}' => text.matches('{').count() < text.matches('}').count(),
+            '.' | ',' | ';' | ':' | '!' | '?' | '%' | '>' | '"' | '\'' | '”' | '’' | '»' | '›'
+            | '…' => true,
+            _ => false,
+        };
+        if !is_trailing {
+            break;
+        }
+        text = &text[..text.len() - last.len_utf8()];
+    }
+    text
+}
+
+fn is_url(text: &str) -> bool {
+    if let Some((scheme, rest)) = text.split_once("://") {
+        let mut scheme_chars = scheme.chars();
+        return scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+            && !rest.is_empty();
+    }
+    if let Some(address) = strip_prefix_ignore_ascii_case(text, "mailto:") {
+        return is_email(address);
+    }
+    strip_prefix_ignore_ascii_case(text, "www.").is_some_and(|domain| {
+        let host = domain.split(['/', '?', '#']).next().unwrap_or_default();
+        is_domain(host)
+    })
+}
+
+fn strip_prefix_ignore_ascii_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+fn is_email(text: &str) -> bool {
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && local
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-'))
+        && is_domain(domain)
+}
+
+/// Return `true` for domain names with at least two labels, ending in an alphabetic one.
+fn is_domain(text: &str) -> bool {
+    let labels: Vec<&str> = text.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.chars().count() >= 2 && tld.chars().all(char::is_alphabetic))
+}
+
+fn is_path(text: &str) -> bool {
+    let is_separator = |c: char| c == '/' || c == '\\';
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '~');
+    let mut chars = text.chars();
+    let absolute = match (chars.next(), chars.next(), chars.next()) {
+        // "/usr", "\\server"
+        (Some('/'), Some(c), _) => is_name_char(c),
+        (Some('\\'), Some('\\'), Some(c)) => is_name_char(c),
+        // "C:\Windows", "C:/Windows"
+        (Some(drive), Some(':'), Some(c)) => drive.is_ascii_alphabetic() && is_separator(c),
+        _ => false,
+    };
+    let relative = ["./", "../", "~/", ".\\", "..\\"]
+        .iter()
+        .any(|prefix| text.len() > prefix.len() && text.starts_with(prefix));
+    if absolute || relative {
+        return true;
+    }
+    // otherwise, there must be a file name with an extension ("src/lib.rs", but not "and/or")
+    let mut segments = text.split(is_separator);
+    let file_name = segments.next_back().unwrap_or_default();
+    let has_extension = file_name.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty()
+            && (1..=5).contains(&extension.len())
+            && extension.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    let mut directories = segments.peekable();
+    has_extension
+        && directories.peek().is_some()
+        && directories.all(|directory| !directory.is_empty() && directory.chars().all(is_name_char))
+}
+
+fn is_number(text: &str) -> bool {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return hex.chars().any(|c| c.is_ascii_hexdigit())
+            && hex.chars().all(|c| c.is_ascii_hexdigit() || c == '_');
+    }
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (text, None),
+    };
+    let is_mantissa = mantissa.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && mantissa.ends_with(|c: char| c.is_ascii_digit())
+        && mantissa.matches('.').count() <= 1
+        && mantissa
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '_'))
+        && !mantissa
+            .as_bytes()
+            .windows(2)
+            .any(|pair| !pair[0].is_ascii_digit() && !pair[1].is_ascii_digit());
+    let is_exponent = exponent.is_none_or(|exponent| {
+        let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    });
+    is_mantissa && is_exponent
+}
+
+fn is_version(text: &str) -> bool {
+    let text = text.strip_prefix(['v', 'V']).unwrap_or(text);
+    let core_len = text.find(['-', '+']).unwrap_or(text.len());
+    let (core, suffix) = text.split_at(core_len);
+    let components: Vec<&str> = core.split('.').collect();
+    let is_core = components.len() >= 2
+        && components.iter().all(|component| {
+            !component.is_empty() && component.chars().all(|c| c.is_ascii_digit())
+        });
+    let is_suffix = suffix.is_empty()
+        || (suffix.len() > 1
+            && suffix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')));
+    is_core && is_suffix
+}
+
+fn is_uuid(text: &str) -> bool {
+    text.len() == 36
+        && text.char_indices().all(|(idx, c)| match idx {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protected(text: &str, protection: &Protection) -> Vec<String> {
+        split_protected(text, protection)
+            .filter(|&(_, is_protected)| is_protected)
+            .map(|(substring, _)| substring.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn split_reproduces_text() {
+        let protection = Protection::default();
+        for text in [
+            "Visit https://example.com, or mail pig@example.com!",
+            "(v1.2.3) ",
+            "  0xFF\n\t42",
+            "no protected text",
+            "",
+        ] {
+            let joined: String = split_protected(text, &protection)
+                .map(|(substring, _)| substring)
+                .collect();
+            assert_eq!(joined, text);
+        }
+    }
+
+    #[test]
+    fn recognizes_classes() {
+        let cases = [
+            (ProtectedClass::Url, "https://example.com/pig?oink=1#top"),
+            (ProtectedClass::Url, "ftp://files.example.com"),
+            (ProtectedClass::Url, "www.example.com/pig"),
+            (ProtectedClass::Url, "mailto:pig@example.com"),
+            (
+                ProtectedClass::Url,
+                "https://en.wikipedia.org/wiki/Pig_(disambiguation)",
+            ),
+            (ProtectedClass::Email, "pig.latin+oink@example.co.uk"),
+            (ProtectedClass::Path, "/usr/local/bin"),
+            (ProtectedClass::Path, "~/.config"),
+            (ProtectedClass::Path, "./pig"),
+            (ProtectedClass::Path, "..\\pig.exe"),
+            (ProtectedClass::Path, "C:\\Windows\\System32"),
+            (ProtectedClass::Path, "src/lib.rs"),
+            (ProtectedClass::Number, "42"),
+            (ProtectedClass::Number, "3.14"),
+            (ProtectedClass::Number, ".5"),
+            (ProtectedClass::Number, "10,000,000"),
+            (ProtectedClass::Number, "6.02e23"),
+            (ProtectedClass::Number, "1E-9"),
+            (ProtectedClass::Number, "0xDEAD_BEEF"),
+            (ProtectedClass::Version, "1.2"),
+            (ProtectedClass::Version, "v2.0.1-beta.1+build.42"),
+            (ProtectedClass::Uuid, "123e4567-e89b-12d3-a456-426614174000"),
+        ];
+        for (class, text) in cases {
+            assert!(class.recognizes(text), "{text} should be a {class:?}");
+            let protection = Protection::none().with(class);
+            assert_eq!(protected(text, &protection), [text]);
+        }
+    }
+
+    #[test]
+    fn not_protected() {
+        let protection = Protection::default();
+        for text in [
+            "pig", "and/or", "24/7", "km/s", "e.g.", "3rd", "1..2", "v2", "@pig", "pig@",
+            "pig@home", "0x", "www.", "https://", "C:", "/", "1,,000",
+        ] {
+            assert_eq!(
+                protected(text, &protection),
+                Vec::<String>::new(),
+                "{text} should not be protected"
+            );
+        }
+    }
+
+    #[test]
+    fn surrounding_punctuation() {
+        let protection = Protection::default();
+        assert_eq!(
+            protected("(see https://example.com/pig).", &protection),
+            ["https://example.com/pig"]
+        );
+        assert_eq!(protected("“42%”", &protection), ["42"]);
+        assert_eq!(
+            protected("<pig@example.com>,", &protection),
+            ["pig@example.com"]
+        );
+        assert_eq!(protected("{v1.0}:", &protection), ["v1.0"]);
+    }
+
+    #[test]
+    fn placeholders_and_whole_text() {
+        let protection = Protection::default().with_placeholders();
+        assert_eq!(
+            protected(
+                "Hi {name}, see https://example.com/{id} or <b>42</b> pigs!",
+                &protection
+            ),
+            [
+                "{name}",
+                "https://example.com/",
+                "{id}",
+                "<b>",
+                "42",
+                "</b>"
+            ]
+        );
+        assert_eq!(
+            protected(
+                "{name} and 42",
+                &Protection::none().with(ProtectedClass::Icu)
+            ),
+            ["{name}"]
+        );
+    }
+
+    #[test]
+    fn toggle_classes() {
+        let protection = Protection::default();
+        assert!(ProtectedClass::WHOLE
+            .into_iter()
+            .all(|class| protection.protects(class)));
+        assert!(!ProtectedClass::PLACEHOLDERS
+            .into_iter()
+            .any(|class| protection.protects(class)));
+        let protection = protection.without(ProtectedClass::Number);
+        assert!(!protection.protects(ProtectedClass::Number));
+        assert!(protection.protects(ProtectedClass::Url));
+        assert_eq!(protected("42 0x1F", &protection), Vec::<String>::new());
+        assert!(Protection::none().is_none());
+        assert_eq!(
+            protected("https://example.com", &Protection::none()),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Pseudo-localization: translating UI strings into Pig-Latin to test internationalization.
+
+use crate::{Protection, Translator};
+
+/// # A pseudo-localizer of UI strings.
+///
+/// Pseudo-localization replaces the text of a user interface with a "translation",
+/// to check that all strings are localizable, and that the layout copes with longer
+/// text, before any real translation exists. Pig-Latin is ideally suited for this,
+/// as it stays readable to English speakers, and is about a third longer than English.
+///
+/// The [`Default`] pseudo-localizer:
+///  - protects format placeholders, such as `{name}`, `%s`, `{{count}}` or `<b>` (see
+///    [`Protection::with_placeholders`]), in addition to the default [`Protection`],
+///  - wraps each string in brackets, to spot truncated and concatenated strings.
+///
+/// The result also reports how much longer the pseudo-localized string is than its
+/// source, see [`PseudoLocalized::expansion`].
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::PseudoLocalizer;
+/// let mut pseudo_localizer = PseudoLocalizer::default();
+/// let pseudo = pseudo_localizer.pseudo_localize("Hello {name}, you have %d <b>new</b> messages");
+/// assert_eq!(
+///     pseudo.text,
+///     "[Ellohay {name}, ouyay avehay %d <b>ewnay</b> essagesmay]"
+/// );
+/// assert_eq!(pseudo.source_chars, 45);
+/// assert_eq!(pseudo.text_chars, 57);
+/// assert!(pseudo.expansion() > 1.2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PseudoLocalizer {
+    translator: Translator,
+    brackets: Option<(String, String)>,
+}
+
+impl PseudoLocalizer {
+    /// Create a pseudo-localizer using `translator`, wrapping strings in "[" and "]".
+    ///
+    /// The translator is used as-is, so its [`Protection`] should include placeholders.
+    pub fn new(translator: Translator) -> Self {
+        PseudoLocalizer {
+            translator,
+            brackets: Some((String::from("["), String::from("]"))),
+        }
+    }
+
+    /// Wrap pseudo-localized strings in `open` and `close` (default: "[" and "]").
+    pub fn with_brackets(mut self, open: impl Into<String>, close: impl Into<String>) -> Self {
+        self.brackets = Some((open.into(), close.into()));
+        self
+    }
+
+    /// Do not wrap pseudo-localized strings in brackets.
+    pub fn without_brackets(mut self) -> Self {
+        self.brackets = None;
+        self
+    }
+
+    /// The translator used for pseudo-localization.
+    pub fn translator(&self) -> &Translator {
+        &self.translator
+    }
+
+    /// Pseudo-localize the UI string `source`.
+    pub fn pseudo_localize(&mut self, source: &str) -> PseudoLocalized {
+        let mut text = String::with_capacity(source.len() + source.len() / 2 + 2);
+        if let Some((open, _)) = &self.brackets {
+            text.push_str(open);
+        }
+        self.translator
+            .translate_into(source, &mut text)
+            .expect("writing to a String should not fail");
+        if let Some((_, close)) = &self.brackets {
+            text.push_str(close);
+        }
+        PseudoLocalized {
+            source_chars: source.chars().count(),
+            text_chars: text.chars().count(),
+            text,
+        }
+    }
+}
+
+impl Default for PseudoLocalizer {
+    fn default() -> Self {
+        let protection = Protection::default().with_placeholders();
+        Self::new(Translator::default().with_protection(protection))
+    }
+}
+
+/// A pseudo-localized string, see [`PseudoLocalizer::pseudo_localize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PseudoLocalized {
+    /// The pseudo-localized string, including any brackets.
+    pub text: String,
+    /// The length of the source string, in `char`s.
+    pub source_chars: usize,
+    /// The length of `text`, in `char`s.
+    pub text_chars: usize,
+}
+
+impl PseudoLocalized {
+    /// The length of the pseudo-localized string relative to its source, e.g. `1.3`
+    /// if it is 30% longer.
+    ///
+    /// Empty sources count as one `char` long.
+    pub fn expansion(&self) -> f64 {
+        self.text_chars as f64 / self.source_chars.max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_protected() {
+        let mut pseudo_localizer = PseudoLocalizer::default().without_brackets();
+        for (source, expected) in [
+            ("Hello %s!", "Ellohay %s!"),
+            ("%1$d pigs of %2$s", "%1$d igspay ofhay %2$s"),
+            ("Welcome, {user.name}!", "Elcomeway, {user.name}!"),
+            ("{{count}} pigs", "{{count}} igspay"),
+            (
+                "<a href=\"/pigs\">Pigs</a> &amp; more",
+                "<a href=\"/pigs\">Igspay</a> &amp; oremay",
+            ),
+            (
+                "{count, plural, =0 {No pigs} one {# pig} other {# pigs}}",
+                "{count, plural, =0 {Onay igspay} one {# igpay} other {# igspay}}",
+            ),
+            ("Visit https://example.com", "Isitvay https://example.com"),
+        ] {
+            assert_eq!(pseudo_localizer.pseudo_localize(source).text, expected);
+        }
+    }
+
+    #[test]
+    fn brackets() {
+        let mut pseudo_localizer = PseudoLocalizer::default();
+        assert_eq!(pseudo_localizer.pseudo_localize("Pig").text, "[Igpay]");
+        let mut pseudo_localizer = pseudo_localizer.with_brackets("⟦", "⟧");
+        assert_eq!(pseudo_localizer.pseudo_localize("Pig").text, "⟦Igpay⟧");
+    }
+
+    #[test]
+    fn expansion() {
+        let mut pseudo_localizer = PseudoLocalizer::default().without_brackets();
+        let pseudo = pseudo_localizer.pseudo_localize("Pig");
+        assert_eq!((pseudo.source_chars, pseudo.text_chars), (3, 5));
+        assert_eq!(pseudo.expansion(), 5.0 / 3.0);
+        let pseudo = pseudo_localizer.pseudo_localize("");
+        assert_eq!(pseudo.expansion(), 0.0);
+        let pseudo = PseudoLocalizer::default().pseudo_localize("");
+        assert_eq!(pseudo.text, "[]");
+        assert_eq!(pseudo.expansion(), 2.0);
+    }
+
+    #[test]
+    fn custom_translator() {
+        let translator = Translator::default().with_protection(Protection::none());
+        let mut pseudo_localizer = PseudoLocalizer::new(translator);
+        assert_eq!(pseudo_localizer.pseudo_localize("%s").text, "[%say]");
+    }
+}
@@ -0,0 +1,238 @@
+//! Mapping byte ranges between English input and its Pig-Latin translation.
+
+use std::ops::Range;
+
+use crate::dialect::{Dialect, OTDOPL};
+use crate::protect::DEFAULT_PROTECTION;
+use crate::tokens::tokens_with;
+use crate::{Glossary, Protection, TokenKind};
+
+/// A token of the input, and the part of the translation it turned into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    /// The kind of the token.
+    pub kind: TokenKind,
+    /// The byte range of the token in the English input.
+    pub source: Range<usize>,
+    /// The byte range of its translation in the Pig-Latin output.
+    pub target: Range<usize>,
+}
+
+impl Mapping {
+    /// Return `true` if the token was copied into the translation byte by byte.
+    fn is_copied(&self) -> bool {
+        self.kind != TokenKind::Word
+    }
+}
+
+/// # A map between byte offsets of an English text and its translation.
+///
+/// Returned by [`translate_with_source_map`], this allows projecting byte ranges
+/// (e.g. of diagnostics, or of highlights in a UI) from the translation back onto the
+/// input, using [`SourceMap::to_source`], and vice versa, using [`SourceMap::to_target`].
+///
+/// Non-word text is copied as-is during translation, so byte ranges within it are
+/// mapped exactly. As words change length and order of their letters, positions inside
+/// a word are mapped onto the whole of the corresponding word.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    /// The mappings of all tokens, in order.
+    ///
+    /// Both their `source` and their `target` ranges are contiguous and cover
+    /// the whole input and translation respectively.
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    /// Project a byte range of the translation onto the English input.
+    ///
+    /// Ranges reaching past the end of the translation are clamped to it.
+    pub fn to_source(&self, target: Range<usize>) -> Range<usize> {
+        self.project(target, |mapping| &mapping.target, |mapping| &mapping.source)
+    }
+
+    /// Project a byte range of the English input onto the translation.
+    ///
+    /// Ranges reaching past the end of the input are clamped to it.
+    pub fn to_target(&self, source: Range<usize>) -> Range<usize> {
+        self.project(source, |mapping| &mapping.source, |mapping| &mapping.target)
+    }
+
+    fn project(
+        &self,
+        range: Range<usize>,
+        from: impl Fn(&Mapping) -> &Range<usize>,
+        to: impl Fn(&Mapping) -> &Range<usize>,
+    ) -> Range<usize> {
+        let Some(last) = self.mappings.last() else {
+            return 0..0;
+        };
+        let from_len = from(last).end;
+        let (range_start, range_end) = (range.start.min(from_len), range.end.min(from_len));
+
+        // the first mapping ending after the start (or the last one)
+        let start_idx = self
+            .mappings
+            .partition_point(|mapping| from(mapping).end <= range_start)
+            .min(self.mappings.len() - 1);
+        let start_mapping = &self.mappings[start_idx];
+        let start = if start_mapping.is_copied() {
+            to(start_mapping).start + (range_start - from(start_mapping).start)
+        } else {
+            to(start_mapping).start
+        };
+        if range_end <= range_start {
+            return start..start;
+        }
+
+        // the last mapping starting before the end
+        let end_idx = self
+            .mappings
+            .partition_point(|mapping| from(mapping).start < range_end)
+            - 1;
+        let end_mapping = &self.mappings[end_idx];
+        let end = if end_mapping.is_copied() {
+            to(end_mapping).start + (range_end - from(end_mapping).start)
+        } else {
+            to(end_mapping).end
+        };
+        start..end
+    }
+}
+
+/// # Translate English into Pig-Latin, and map between their byte offsets.
+///
+/// Like [`translate`](crate::translate), but also returns a [`SourceMap`], which
+/// relates byte ranges of the translation to those of the input.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::translate_with_source_map;
+/// let english = "Hello, world!";
+/// let (translated, source_map) = translate_with_source_map(english);
+/// assert_eq!(translated, "Ellohay, orldway!");
+///
+/// // "orldway" in the translation comes from "world" in the input
+/// assert_eq!(source_map.to_source(9..16), 7..12);
+/// assert_eq!(&english[source_map.to_source(9..16)], "world");
+///
+/// // and back
+/// assert_eq!(&translated[source_map.to_target(7..12)], "orldway");
+///
+/// // punctuation maps exactly
+/// assert_eq!(source_map.to_target(12..13), 16..17);
+/// ```
+pub fn translate_with_source_map(english: &str) -> (String, SourceMap) {
+    translate_with_source_map_with(english, &OTDOPL, &DEFAULT_PROTECTION, None)
+}
+
+/// Implement [`translate_with_source_map`] for any `dialect`, `protection` and `glossary`.
+pub(crate) fn translate_with_source_map_with(
+    english: &str,
+    dialect: &Dialect,
+    protection: &Protection,
+    glossary: Option<&Glossary>,
+) -> (String, SourceMap) {
+    let mut translated = String::with_capacity(english.len() + english.len() / 3);
+    let mut mappings = Vec::new();
+    for token in tokens_with(english, dialect, protection, glossary) {
+        let target_start = translated.len();
+        translated.push_str(&token.translated);
+        mappings.push(Mapping {
+            kind: token.kind,
+            source: token.span,
+            target: target_start..translated.len(),
+        });
+    }
+    (translated, SourceMap { mappings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translate;
+
+    const ENGLISH: &str = "This is all quite easy, is it not?\n\t“Early-Adopters” are ecstatic!";
+
+    #[test]
+    fn translation_unchanged() {
+        let (translated, _) = translate_with_source_map(ENGLISH);
+        assert_eq!(translated, translate(ENGLISH));
+    }
+
+    #[test]
+    fn mappings_are_contiguous() {
+        let (translated, source_map) = translate_with_source_map(ENGLISH);
+        let mut last_source_end = 0;
+        let mut last_target_end = 0;
+        for mapping in source_map.mappings() {
+            assert_eq!(mapping.source.start, last_source_end);
+            assert_eq!(mapping.target.start, last_target_end);
+            last_source_end = mapping.source.end;
+            last_target_end = mapping.target.end;
+        }
+        assert_eq!(last_source_end, ENGLISH.len());
+        assert_eq!(last_target_end, translated.len());
+    }
+
+    #[test]
+    fn words_map_onto_words() {
+        let (translated, source_map) = translate_with_source_map(ENGLISH);
+        for (english_word, pig_latin_word) in [
+            ("quite", "itequay"),
+            ("Adopters", "Adoptershay"),
+            ("ecstatic", "ecstatichay"),
+        ] {
+            let source = ENGLISH.find(english_word).unwrap();
+            let source = source..source + english_word.len();
+            let target = translated.find(pig_latin_word).unwrap();
+            let target = target..target + pig_latin_word.len();
+            assert_eq!(source_map.to_target(source.clone()), target);
+            assert_eq!(source_map.to_source(target.clone()), source);
+            // partial words map onto whole words
+            assert_eq!(
+                source_map.to_source(target.start + 1..target.end - 1),
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn spanning_ranges() {
+        let english = "Hello, big world!";
+        let (translated, source_map) = translate_with_source_map(english);
+        assert_eq!(translated, "Ellohay, igbay orldway!");
+        // ", big" -> ", igbay"
+        assert_eq!(source_map.to_target(5..10), 7..14);
+        // "lohay, ig" -> "Hello, big"
+        assert_eq!(source_map.to_source(2..11), 0..10);
+        // everything
+        assert_eq!(source_map.to_source(0..translated.len()), 0..english.len());
+        assert_eq!(source_map.to_target(0..100), 0..translated.len());
+    }
+
+    #[test]
+    fn empty_ranges() {
+        let (_, source_map) = translate_with_source_map("Hello, world!");
+        // within punctuation and whitespace: exact
+        assert_eq!(source_map.to_target(6..6), 8..8);
+        // within a word: its start
+        assert_eq!(source_map.to_target(9..9), 9..9);
+        assert_eq!(source_map.to_source(12..12), 7..7);
+        // at the end
+        assert_eq!(source_map.to_target(13..13), 17..17);
+    }
+
+    #[test]
+    fn empty_input() {
+        let (translated, source_map) = translate_with_source_map("");
+        assert_eq!(translated, "");
+        assert_eq!(source_map.to_source(0..5), 0..0);
+        assert_eq!(source_map.to_target(0..5), 0..0);
+    }
+}
@@ -0,0 +1,266 @@
+//! Translating subtitles (SubRip `.srt` and WebVTT `.vtt` files).
+
+use crate::html::translate_text;
+use crate::{PigLatinError, Translator};
+
+/// # Translate SubRip subtitles (an `.srt` file) into Pig-Latin.
+///
+/// Translates the text of each cue. Everything else is kept byte for byte: cue
+/// numbers, timings (including any positioning after them), formatting tags such as
+/// `<i>` or `<font color="red">`, override tags such as `{\an8}`, and the layout of
+/// the file.
+///
+/// Returns [`PigLatinError::Syntax`] if a cue has no timing line.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_srt, Translator};
+/// let subtitles = "\
+/// 1
+/// 00:00:01,000 --> 00:00:03,500
+/// Hello, <i>little</i> pig!
+///
+/// 2
+/// 00:00:04,000 --> 00:00:06,000 X1:100 X2:600 Y1:50 Y2:80
+/// {\\an8}Oink.
+/// ";
+/// assert_eq!(
+///     translate_srt(subtitles, &mut Translator::default()).unwrap(),
+///     "\
+/// 1
+/// 00:00:01,000 --> 00:00:03,500
+/// Ellohay, <i>ittlelay</i> igpay!
+///
+/// 2
+/// 00:00:04,000 --> 00:00:06,000 X1:100 X2:600 Y1:50 Y2:80
+/// {\\an8}Oinkhay.
+/// "
+/// );
+/// ```
+pub fn translate_srt(
+    subtitles: &str,
+    translator: &mut Translator,
+) -> Result<String, PigLatinError> {
+    translate_cues(subtitles, Format::Srt, translator)
+}
+
+/// # Translate WebVTT subtitles (a `.vtt` file) into Pig-Latin.
+///
+/// Translates the text of each cue. Everything else is kept byte for byte: the
+/// header, `NOTE`, `STYLE` and `REGION` blocks, cue identifiers, timings and settings
+/// (such as `align:start`), tags such as `<i>`, `<c.yellow>` or `<v Speaker>`, character
+/// references such as `&amp;`, and the layout of the file.
+///
+/// Returns [`PigLatinError::Syntax`] if the file does not start with "WEBVTT", or
+/// if a cue has no timing line.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{translate_webvtt, Translator};
+/// let subtitles = "\
+/// WEBVTT
+///
+/// NOTE Pigs talking
+///
+/// intro
+/// 00:01.000 --> 00:03.500 align:start position:10%
+/// <v Mr. Pig>Hello there!</v>
+/// ";
+/// assert_eq!(
+///     translate_webvtt(subtitles, &mut Translator::default()).unwrap(),
+///     "\
+/// WEBVTT
+///
+/// NOTE Pigs talking
+///
+/// intro
+/// 00:01.000 --> 00:03.500 align:start position:10%
+/// <v Mr. Pig>Ellohay erethay!</v>
+/// "
+/// );
+/// ```
+pub fn translate_webvtt(
+    subtitles: &str,
+    translator: &mut Translator,
+) -> Result<String, PigLatinError> {
+    translate_cues(subtitles, Format::WebVtt, translator)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Srt,
+    WebVtt,
+}
+
+/// Where in a block of lines (separated by blank lines) the current line is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// After a blank line, or at the start of the file.
+    BetweenBlocks,
+    /// In a block that is copied as-is, such as the header or a note.
+    Verbatim,
+    /// After the number or identifier of a cue, before its timing.
+    Identifier,
+    /// After the timing of a cue.
+    CueText,
+}
+
+/// Implement [`translate_srt`] and [`translate_webvtt`].
+fn translate_cues(
+    subtitles: &str,
+    format: Format,
+    translator: &mut Translator,
+) -> Result<String, PigLatinError> {
+    let mut translated = String::with_capacity(subtitles.len() * 2);
+    let mut state = State::BetweenBlocks;
+    let mut is_first_block = true;
+    let mut line_idx = 0;
+    for line in subtitles.split_inclusive('\n') {
+        line_idx += 1;
+        let content = line.trim_end_matches(['\n', '\r']);
+        let syntax_error = |message: &str| PigLatinError::Syntax {
+            line: line_idx,
+            message: message.to_string(),
+        };
+        if content.trim().is_empty() {
+            if state == State::Identifier {
+                return Err(syntax_error("expected a cue timing"));
+            }
+            state = State::BetweenBlocks;
+            translated.push_str(line);
+            continue;
+        }
+        match state {
+            State::BetweenBlocks => {
+                let is_webvtt_header = is_first_block
+                    && starts_with_keyword(content.trim_start_matches('\u{feff}'), "WEBVTT");
+                if format == Format::WebVtt && is_first_block && !is_webvtt_header {
+                    return Err(syntax_error("expected \"WEBVTT\""));
+                }
+                let is_verbatim = is_webvtt_header
+                    || format == Format::WebVtt
+                        && ["NOTE", "STYLE", "REGION"]
+                            .into_iter()
+                            .any(|keyword| starts_with_keyword(content, keyword));
+                state = if is_verbatim {
+                    State::Verbatim
+                } else if content.contains("-->") {
+                    State::CueText
+                } else {
+                    State::Identifier
+                };
+                is_first_block = false;
+                translated.push_str(line);
+            }
+            State::Verbatim => translated.push_str(line),
+            State::Identifier => {
+                if !content.contains("-->") {
+                    return Err(syntax_error("expected a cue timing"));
+                }
+                state = State::CueText;
+                translated.push_str(line);
+            }
+            State::CueText => {
+                translate_cue_text(content, format, translator, &mut translated);
+                translated.push_str(&line[content.len()..]);
+            }
+        }
+    }
+    if state == State::Identifier {
+        return Err(PigLatinError::Syntax {
+            line: line_idx,
+            message: String::from("expected a cue timing"),
+        });
+    }
+    if format == Format::WebVtt && is_first_block {
+        return Err(PigLatinError::Syntax {
+            line: 1,
+            message: String::from("expected \"WEBVTT\""),
+        });
+    }
+    Ok(translated)
+}
+
+/// Return `true` if `line` is `keyword`, or starts with it followed by whitespace.
+fn starts_with_keyword(line: &str, keyword: &str) -> bool {
+    line.strip_prefix(keyword)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+/// Translate a line of cue text, copying tags (and, for SubRip, override tags). A "<"
+/// only starts a tag if followed by a letter, a digit (of a timestamp) or "/".
+fn translate_cue_text(
+    text: &str,
+    format: Format,
+    translator: &mut Translator,
+    output: &mut String,
+) {
+    let mut rest = text;
+    loop {
+        let tag = rest.match_indices(['<', '{']).find_map(|(idx, open)| {
+            let close = match open {
+                // a tag name, an end tag, or a timestamp
+                "<" if rest[idx + 1..]
+                    .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '/') =>
+                {
+                    '>'
+                }
+                _ if format == Format::Srt && rest[idx..].starts_with("{\\") => '}',
+                _ => return None,
+            };
+            rest[idx..].find(close).map(|len| (idx, idx + len + 1))
+        });
+        let Some((start, end)) = tag else {
+            translate_text(rest, translator, output);
+            return;
+        };
+        translate_text(&rest[..start], translator, output);
+        output.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srt() {
+        let subtitles = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,000\r\n<font color=\"#ff0000\">Pink</font> pig\r\nTwo lines\r\n\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\n- Oink &amp; {oink}\r\nA < b and c > d\r\n";
+        assert_eq!(
+            translate_srt(subtitles, &mut Translator::default()).unwrap(),
+            "\u{feff}1\r\n00:00:01,000 --> 00:00:02,000\r\n<font color=\"#ff0000\">Inkpay</font> igpay\r\nOtway ineslay\r\n\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\n- Oinkhay &amp; {oinkhay}\r\nAhay < bay andhay cay > day\r\n"
+        );
+    }
+
+    #[test]
+    fn webvtt_blocks() {
+        let subtitles = "WEBVTT - Pig talk\nKind: captions\n\nSTYLE\n::cue(v[voice=\"Pig\"]) { color: pink }\n\nREGION\nid:pen\n\nNOTE\nThis pig\nis a note\n\n00:00.000 --> 00:01.000 region:pen\n<c.pink>Pink</c> <00:00.500><b>pig</b>\n\nNOTES about pigs\n00:02.000 --> 00:03.000\nNotes\n";
+        assert_eq!(
+            translate_webvtt(subtitles, &mut Translator::default()).unwrap(),
+            "WEBVTT - Pig talk\nKind: captions\n\nSTYLE\n::cue(v[voice=\"Pig\"]) { color: pink }\n\nREGION\nid:pen\n\nNOTE\nThis pig\nis a note\n\n00:00.000 --> 00:01.000 region:pen\n<c.pink>Inkpay</c> <00:00.500><b>igpay</b>\n\nNOTES about pigs\n00:02.000 --> 00:03.000\nOtesnay\n"
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        for (subtitles, format, line) in [
+            ("1\nHello\n", Format::Srt, 2),
+            ("1\n\n00:00:01,000 --> 00:00:02,000\nHi\n", Format::Srt, 2),
+            ("1\n", Format::Srt, 1),
+            ("\n00:00.000 --> 00:01.000\nHi\n", Format::WebVtt, 2),
+            ("WEBVTTX\n", Format::WebVtt, 1),
+            ("", Format::WebVtt, 1),
+            ("WEBVTT\n\nintro\nHi\n", Format::WebVtt, 4),
+        ] {
+            match translate_cues(subtitles, format, &mut Translator::default()) {
+                Err(PigLatinError::Syntax {
+                    line: error_line, ..
+                }) => assert_eq!(error_line, line, "{subtitles:?}"),
+                other => panic!("expected a syntax error for {subtitles:?}, got {other:?}"),
+            }
+        }
+    }
+}
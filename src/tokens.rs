@@ -0,0 +1,202 @@
+//! The segmentation of text used by [`translate`](crate::translate), as an iterator of tokens.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::details::{byte_offset, split_pieces, translate_word_glossary_inplace, Piece};
+use crate::dialect::{Dialect, OTDOPL};
+use crate::protect::DEFAULT_PROTECTION;
+use crate::{Glossary, Protection};
+
+/// The kind of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// A word, which is translated.
+    Word,
+    /// A run of whitespace, which is kept as-is.
+    Whitespace,
+    /// A run of anything else (punctuation, symbols, emoji, ...), which is kept as-is.
+    Punctuation,
+    /// Text that is not English, such as a URL, which is kept as-is, see
+    /// [`Protection`](crate::Protection).
+    Protected,
+}
+
+/// A piece of English text, and its translation, see [`tokens`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// What kind of text this is.
+    pub kind: TokenKind,
+    /// The byte range of the token in the English input.
+    pub span: Range<usize>,
+    /// The English text of the token, i.e. the input sliced by `span`.
+    pub text: &'a str,
+    /// The Pig-Latin translation of the token.
+    ///
+    /// This is the same as `text`, unless the token is a [`TokenKind::Word`].
+    pub translated: Cow<'a, str>,
+}
+
+/// # Split English text into tokens, and translate them.
+///
+/// This exposes the tokenization used by [`translate`](crate::translate): concatenating the
+/// `translated` text of all tokens gives the translation of `english`. Each [`Token`] also
+/// carries its kind, and its byte span in `english`.
+///
+/// Words are yielded one by one, whereas neighbouring non-words of the same kind are
+/// joined into one token.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{tokens, TokenKind};
+/// let tokens: Vec<_> = tokens("Hello, world!").collect();
+/// assert_eq!(tokens[0].kind, TokenKind::Word);
+/// assert_eq!(tokens[0].span, 0..5);
+/// assert_eq!(tokens[0].translated, "Ellohay");
+/// assert_eq!(tokens[1].kind, TokenKind::Punctuation);
+/// assert_eq!(tokens[2].kind, TokenKind::Whitespace);
+/// assert_eq!(tokens[3].text, "world");
+/// assert_eq!(tokens[3].span, 7..12);
+/// ```
+pub fn tokens(english: &str) -> impl Iterator<Item = Token<'_>> {
+    tokens_with(english, &OTDOPL, &DEFAULT_PROTECTION, None)
+}
+
+/// Implement [`tokens`] for any `dialect`, `protection` and `glossary`, see
+/// [`Translator::tokens`](crate::Translator::tokens).
+pub(crate) fn tokens_with<'a>(
+    english: &'a str,
+    dialect: &'a Dialect,
+    protection: &'a Protection,
+    glossary: Option<&'a Glossary>,
+) -> impl Iterator<Item = Token<'a>> + 'a {
+    let mut pieces = split_pieces(english, dialect, protection)
+        .map(|(substring, piece)| (substring, kind_of(substring, piece)))
+        .peekable();
+    std::iter::from_fn(move || {
+        let (first, kind) = pieces.next()?;
+        let start = byte_offset(english, first);
+        let mut end = start + first.len();
+        if kind != TokenKind::Word && kind != TokenKind::Protected {
+            while let Some((next, _)) = pieces.next_if(|&(_, next_kind)| next_kind == kind) {
+                end += next.len();
+            }
+        }
+        let text = &english[start..end];
+        let translated = match kind {
+            TokenKind::Word => {
+                let mut translated = String::with_capacity(text.len() + 3);
+                let mut moved = String::new();
+                translate_word_glossary_inplace(
+                    text,
+                    dialect,
+                    glossary,
+                    &mut moved,
+                    &mut translated,
+                );
+                Cow::Owned(translated)
+            }
+            TokenKind::Whitespace | TokenKind::Punctuation | TokenKind::Protected => {
+                Cow::Borrowed(text)
+            }
+        };
+        Some(Token {
+            kind,
+            span: start..end,
+            text,
+            translated,
+        })
+    })
+}
+
+fn kind_of(substring: &str, piece: Piece) -> TokenKind {
+    match piece {
+        Piece::Word => TokenKind::Word,
+        Piece::Protected => TokenKind::Protected,
+        Piece::NonWord if substring.chars().all(char::is_whitespace) => TokenKind::Whitespace,
+        Piece::NonWord => TokenKind::Punctuation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translate;
+
+    #[test]
+    fn concatenation_is_translation() {
+        for english in [
+            "This is all quite easy, is it not?\n\tEarly-Adopters are ecstatic!",
+            "“Don’t,” she said — «pigs’ latin…»  🐷",
+            "",
+        ] {
+            let translated: String = tokens(english).map(|token| token.translated).collect();
+            assert_eq!(translated, translate(english));
+        }
+    }
+
+    #[test]
+    fn spans_cover_input() {
+        let english = "“Don’t,” she said — «pigs’ latin…»  🐷";
+        let mut last_end = 0;
+        for token in tokens(english) {
+            assert_eq!(token.span.start, last_end);
+            assert_eq!(&english[token.span.clone()], token.text);
+            last_end = token.span.end;
+        }
+        assert_eq!(last_end, english.len());
+    }
+
+    #[test]
+    fn dialect() {
+        let translator = crate::Translator::new(Dialect::builder().vowel_suffix("way").build());
+        let translated: Vec<_> = translator
+            .tokens("I am")
+            .map(|token| token.translated)
+            .collect();
+        assert_eq!(translated, ["Iway", " ", "amway"]);
+    }
+
+    #[test]
+    fn kinds() {
+        let kinds: Vec<_> = tokens("Oh, no...  don't\n\n!")
+            .map(|token| (token.kind, token.text))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (TokenKind::Word, "Oh"),
+                (TokenKind::Punctuation, ","),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Word, "no"),
+                (TokenKind::Punctuation, "..."),
+                (TokenKind::Whitespace, "  "),
+                (TokenKind::Word, "don't"),
+                (TokenKind::Whitespace, "\n\n"),
+                (TokenKind::Punctuation, "!"),
+            ]
+        );
+    }
+
+    #[test]
+    fn protected() {
+        let kinds: Vec<_> = tokens("Mail pig@example.com, 42 times!")
+            .map(|token| (token.kind, token.text))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (TokenKind::Word, "Mail"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Protected, "pig@example.com"),
+                (TokenKind::Punctuation, ","),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Protected, "42"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Word, "times"),
+                (TokenKind::Punctuation, "!"),
+            ]
+        );
+    }
+}
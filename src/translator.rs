@@ -0,0 +1,357 @@
+//! A reusable translator, e.g. for streams of text.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::details::{translate_text, WordBuffers};
+use crate::source_map::translate_with_source_map_with;
+use crate::tokens::tokens_with;
+use crate::{Dialect, Glossary, Protection, SourceMap, Token};
+
+/// The number of bytes read from a stream at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of bytes without whitespace after which a stream is cut at a word
+/// boundary instead.
+const MAX_PENDING: usize = 16 * CHUNK_SIZE;
+
+/// # A reusable English to Pig-Latin translator.
+///
+/// A `Translator` bundles the settings for a translation: the [`Dialect`], the
+/// [`Protection`] of text that is not English, and an optional [`Glossary`]. It
+/// provides ways of translating text beyond the [`translate`](crate::translate)
+/// function, such as [`Translator::translate_stream`].
+///
+/// It also holds scratch buffers, which are re-used between calls. Translating many
+/// short texts with the same `Translator`, e.g. via [`Translator::translate_into`],
+/// thus avoids allocating anew for each one.
+///
+/// The [`Default`] translator translates into [OTDoPL](crate#one-true-dialect).
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::Translator;
+/// let mut translator = Translator::default();
+/// let mut translated = String::new();
+/// for message in ["Hello", "world"] {
+///     translated.clear();
+///     translator.translate_into(message, &mut translated).unwrap();
+///     println!("{translated}");
+/// }
+/// assert_eq!(translated, "orldway");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Translator {
+    dialect: Dialect,
+    protection: Protection,
+    glossary: Option<Glossary>,
+    buffers: WordBuffers,
+}
+
+impl Translator {
+    /// Create a translator into `dialect`.
+    pub fn new(dialect: Dialect) -> Self {
+        Translator {
+            dialect,
+            protection: Protection::default(),
+            glossary: None,
+            buffers: WordBuffers::default(),
+        }
+    }
+
+    /// Set the classes of text that are copied verbatim (default: [`Protection::default`]).
+    pub fn with_protection(mut self, protection: Protection) -> Self {
+        self.protection = protection;
+        self
+    }
+
+    /// Set a glossary of words with a fixed translation (default: none).
+    pub fn with_glossary(mut self, glossary: Glossary) -> Self {
+        self.glossary = Some(glossary);
+        self
+    }
+
+    /// The dialect this translator translates into.
+    pub fn dialect(&self) -> &Dialect {
+        &self.dialect
+    }
+
+    /// The classes of text this translator copies verbatim.
+    pub fn protection(&self) -> &Protection {
+        &self.protection
+    }
+
+    /// The glossary of this translator, if any.
+    pub fn glossary(&self) -> Option<&Glossary> {
+        self.glossary.as_ref()
+    }
+
+    /// Translate `english` into Pig-Latin, see [`translate`](crate::translate).
+    pub fn translate(&mut self, english: &str) -> String {
+        let mut translated = String::with_capacity(english.len() + english.len() / 3);
+        self.translate_into(english, &mut translated)
+            .expect("writing to a String should not fail");
+        translated
+    }
+
+    /// Translate `english` into Pig-Latin, writing into `sink`.
+    ///
+    /// See [`translate_into`](crate::translate_into).
+    pub fn translate_into(&mut self, english: &str, sink: &mut impl fmt::Write) -> fmt::Result {
+        translate_text(
+            english,
+            &self.dialect,
+            &self.protection,
+            self.glossary.as_ref(),
+            &mut self.buffers,
+            |piece| sink.write_str(piece),
+        )
+    }
+
+    /// Translate `english` into Pig-Latin, writing UTF-8 into `writer`.
+    ///
+    /// See [`translate_to_io`](crate::translate_to_io).
+    pub fn translate_to_io(&mut self, english: &str, writer: &mut impl Write) -> io::Result<()> {
+        translate_text(
+            english,
+            &self.dialect,
+            &self.protection,
+            self.glossary.as_ref(),
+            &mut self.buffers,
+            |piece| writer.write_all(piece.as_bytes()),
+        )
+    }
+
+    /// Split `english` into tokens, and translate them, see [`tokens`](crate::tokens).
+    pub fn tokens<'a>(&'a self, english: &'a str) -> impl Iterator<Item = Token<'a>> + 'a {
+        tokens_with(
+            english,
+            &self.dialect,
+            &self.protection,
+            self.glossary.as_ref(),
+        )
+    }
+
+    /// Translate `english` into Pig-Latin, and map between their byte offsets.
+    ///
+    /// See [`translate_with_source_map`](crate::translate_with_source_map).
+    pub fn translate_with_source_map(&self, english: &str) -> (String, SourceMap) {
+        translate_with_source_map_with(
+            english,
+            &self.dialect,
+            &self.protection,
+            self.glossary.as_ref(),
+        )
+    }
+
+    /// # Translate a stream of UTF-8 English text into Pig-Latin.
+    ///
+    /// Reads `reader` in chunks until the end of file, and writes the translation of
+    /// each chunk to `writer` as soon as possible. Chunks are only ever cut after
+    /// whitespace, so that words (and multi-byte characters) split across reads are
+    /// translated as a whole. Runs of over a megabyte without any whitespace are cut
+    /// after their last word boundary instead (or anywhere, if they are a single word),
+    /// so that the memory used stays bounded.
+    ///
+    /// `writer` is flushed after each translated chunk.
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the input is not valid UTF-8,
+    /// and passes through any error from `reader` or `writer` (other than
+    /// [`io::ErrorKind::Interrupted`], which is retried).
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use pig_latin::Translator;
+    /// let mut translated = Vec::new();
+    /// Translator::default()
+    ///     .translate_stream("Hello world!\nOink.".as_bytes(), &mut translated)
+    ///     .unwrap();
+    /// assert_eq!(translated, b"Ellohay orldway!\nOinkhay.");
+    /// ```
+    pub fn translate_stream(
+        &mut self,
+        mut reader: impl Read,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        let mut pending: Vec<u8> = Vec::with_capacity(CHUNK_SIZE);
+        // the length of the start of `pending` known to be valid UTF-8 without whitespace
+        let mut scanned = 0;
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        loop {
+            let n_read = match reader.read(&mut chunk) {
+                Ok(n_read) => n_read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            let at_eof = n_read == 0;
+            pending.extend_from_slice(&chunk[..n_read]);
+
+            let new = match std::str::from_utf8(&pending[scanned..]) {
+                Ok(new) => new,
+                // an incomplete character at the end may be completed by the next read
+                Err(error) if error.error_len().is_none() && !at_eof => {
+                    std::str::from_utf8(&pending[scanned..scanned + error.valid_up_to()])
+                        .expect("prefix should be valid UTF-8")
+                }
+                Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+            };
+            let valid_len = scanned + new.len();
+            let cut = if at_eof {
+                valid_len
+            } else if let Some(ws_idx) = new.rfind(char::is_whitespace) {
+                scanned + ws_idx + new[ws_idx..].chars().next().map_or(0, char::len_utf8)
+            } else if valid_len > MAX_PENDING {
+                let valid = std::str::from_utf8(&pending[..valid_len])
+                    .expect("scanned text should be valid UTF-8");
+                match valid.split_word_bound_indices().next_back() {
+                    Some((word_idx, _)) if word_idx > 0 => word_idx,
+                    _ => valid_len,
+                }
+            } else {
+                0
+            };
+            scanned = valid_len - cut;
+            if cut > 0 {
+                let text = std::str::from_utf8(&pending[..cut])
+                    .expect("scanned text should be valid UTF-8");
+                self.translate_to_io(text, &mut writer)?;
+                writer.flush()?;
+                pending.drain(..cut);
+            }
+            if at_eof {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translate;
+
+    /// A reader returning at most `max_read` bytes per call to `read`.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        max_read: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n_read = self.max_read.min(buf.len()).min(self.data.len());
+            buf[..n_read].copy_from_slice(&self.data[..n_read]);
+            self.data = &self.data[n_read..];
+            Ok(n_read)
+        }
+    }
+
+    fn translate_trickle(english: &str, max_read: usize) -> io::Result<String> {
+        let mut translated = Vec::new();
+        let reader = Trickle {
+            data: english.as_bytes(),
+            max_read,
+        };
+        Translator::default().translate_stream(reader, &mut translated)?;
+        Ok(String::from_utf8(translated).unwrap())
+    }
+
+    #[test]
+    fn stream_like_translate() {
+        let english = "This is all quite easy, is it not?\n\tEarly-Adopters are ecstatic!\r\n";
+        for max_read in [1, 2, 3, 5, 7, 64, CHUNK_SIZE] {
+            assert_eq!(
+                translate_trickle(english, max_read).unwrap(),
+                translate(english)
+            );
+        }
+    }
+
+    #[test]
+    fn stream_split_multibyte_chars() {
+        let english = "Æorlingas wield ﬁre, naïvely  —  Straße!";
+        for max_read in [1, 2, 3] {
+            assert_eq!(
+                translate_trickle(english, max_read).unwrap(),
+                translate(english)
+            );
+        }
+    }
+
+    #[test]
+    fn stream_long_word() {
+        let english = "a".repeat(3 * CHUNK_SIZE) + " pig";
+        assert_eq!(
+            translate_trickle(&english, CHUNK_SIZE).unwrap(),
+            translate(&english)
+        );
+    }
+
+    #[test]
+    fn stream_without_whitespace() {
+        let english = format!("{},", "pig".repeat(300)).repeat((MAX_PENDING + CHUNK_SIZE) / 900);
+        assert_eq!(
+            translate_trickle(&english, CHUNK_SIZE).unwrap(),
+            translate(&english)
+        );
+        let word = "a".repeat(MAX_PENDING + CHUNK_SIZE);
+        let translated = translate_trickle(&word, CHUNK_SIZE).unwrap();
+        assert!(translated.len() > word.len() && translated.len() < 2 * word.len());
+    }
+
+    #[test]
+    fn stream_invalid_utf8() {
+        let mut translated = Vec::new();
+        let error = Translator::default()
+            .translate_stream(&b"pig \xff latin"[..], &mut translated)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let error = Translator::default()
+            .translate_stream(&b"pig \xc3"[..], &mut translated)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn protection() {
+        let english = "Email pig@example.com about 0x2A";
+        assert_eq!(
+            Translator::default().translate(english),
+            "Emailhay pig@example.com abouthay 0x2A"
+        );
+        let mut translator = Translator::default().with_protection(Protection::none());
+        assert_eq!(
+            translator.translate(english),
+            "Emailhay igpay@examplehay.omcay abouthay A0x2AY"
+        );
+    }
+
+    #[test]
+    fn glossary() {
+        let glossary = Glossary::new()
+            .keep("iPhone")
+            .keep("NASA")
+            .translate_as("hello", "ahoy")
+            .translate_as("it's", "'tis");
+        let mut translator = Translator::default().with_glossary(glossary);
+        assert_eq!(
+            translator.translate("Hello, NASA! My IPHONE's screen says HELLO. It's nasa-approved."),
+            "Ahoy, NASA! Myay IPHONE's eenscray ayssay AHOY. 'tis nasa-approvedhay."
+        );
+        let tokens: Vec<_> = translator
+            .tokens("hello iphone")
+            .map(|token| token.translated)
+            .collect();
+        assert_eq!(tokens, ["ahoy", " ", "iphone"]);
+        let (translated, _) = translator.translate_with_source_map("Hello");
+        assert_eq!(translated, "Ahoy");
+    }
+
+    #[test]
+    fn stream_empty() {
+        assert_eq!(translate_trickle("", 1).unwrap(), "");
+    }
+}
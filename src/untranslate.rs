@@ -0,0 +1,485 @@
+//! Reverse translation, from Pig-Latin back into English.
+//!
+//! Translating into Pig-Latin loses information: a word ending in "hay" may
+//! have started with a vowel, or it may have started with an "h" that was
+//! moved to the back. Because of this, untranslating a word yields every
+//! English word that [`translate_word`](crate::translate_word) maps onto it, rather than a guess.
+//! To pick the most likely candidates, rank them with a [`WordModel`].
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::details::{split_pieces, split_possessive, split_words, Piece};
+use crate::dialect::{Dialect, OTDOPL};
+use crate::protect::DEFAULT_PROTECTION;
+use crate::word_model::WordModel;
+use crate::{apply_casing_like, translate_word_with};
+
+/// A piece of text produced by [`untranslate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Non-word text (whitespace, punctuation), copied from the input as-is.
+    Verbatim(String),
+    /// A Pig-Latin word, and every English word translating into it.
+    ///
+    /// `candidates` is empty if the word could not have been produced by
+    /// [`translate_word`](crate::translate_word).
+    Word {
+        pig_latin: String,
+        candidates: Vec<String>,
+    },
+}
+
+/// The result of [`untranslate`]: the input, split into [`Segment`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Untranslation {
+    segments: Vec<Segment>,
+}
+
+impl Untranslation {
+    /// The words and non-word substrings of the untranslated text, in order.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Return `true` if any word has more than one English candidate.
+    pub fn is_ambiguous(&self) -> bool {
+        self.segments.iter().any(|segment| match segment {
+            Segment::Verbatim(_) => false,
+            Segment::Word { candidates, .. } => candidates.len() > 1,
+        })
+    }
+
+    /// Return the English text, if every word has exactly one candidate.
+    pub fn unambiguous(&self) -> Option<String> {
+        let mut english = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Verbatim(text) => english.push_str(text),
+                Segment::Word { candidates, .. } if candidates.len() == 1 => {
+                    english.push_str(&candidates[0])
+                }
+                Segment::Word { .. } => return None,
+            }
+        }
+        Some(english)
+    }
+
+    /// Rank the candidates of every word by how common they are according to `model`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use pig_latin::{untranslate, WordFrequencies};
+    /// let ranked = untranslate("Ellohay orldway!").rank(&WordFrequencies::default());
+    /// assert_eq!(ranked.to_english(), "Hello world!");
+    /// ```
+    pub fn rank(&self, model: &impl WordModel) -> RankedUntranslation {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Verbatim(text) => RankedSegment::Verbatim(text.clone()),
+                Segment::Word {
+                    pig_latin,
+                    candidates,
+                } => RankedSegment::Word(RankedWord::new(pig_latin, candidates, model)),
+            })
+            .collect();
+        RankedUntranslation { segments }
+    }
+}
+
+/// A word of an [`Untranslation`], with its candidates ranked by a [`WordModel`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedWord {
+    /// The Pig-Latin word.
+    pub pig_latin: String,
+    /// Every candidate with its confidence, most likely first.
+    ///
+    /// The confidences are between zero and one, and sum to one.
+    pub alternatives: Vec<(String, f64)>,
+}
+
+impl RankedWord {
+    fn new(pig_latin: &str, candidates: &[String], model: &impl WordModel) -> Self {
+        // add-one smoothing, so that candidates unknown to the model keep a chance
+        let scores: Vec<f64> = candidates
+            .iter()
+            .map(|candidate| model.frequency(&candidate.to_lowercase()) + 1.0)
+            .collect();
+        let total: f64 = scores.iter().sum();
+        let mut alternatives: Vec<(String, f64)> = candidates
+            .iter()
+            .zip(scores)
+            .map(|(candidate, score)| (candidate.clone(), score / total))
+            .collect();
+        // stable, so ties keep the order of the candidates
+        alternatives.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+        RankedWord {
+            pig_latin: pig_latin.to_string(),
+            alternatives,
+        }
+    }
+
+    /// The most likely English word, if there is any candidate.
+    pub fn best(&self) -> Option<&str> {
+        self.alternatives
+            .first()
+            .map(|(candidate, _)| candidate.as_str())
+    }
+
+    /// The confidence in [`RankedWord::best`], or zero if there is no candidate.
+    pub fn confidence(&self) -> f64 {
+        self.alternatives
+            .first()
+            .map_or(0.0, |(_, confidence)| *confidence)
+    }
+}
+
+/// A piece of text produced by [`Untranslation::rank`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RankedSegment {
+    /// Non-word text (whitespace, punctuation), copied from the input as-is.
+    Verbatim(String),
+    /// A Pig-Latin word, with its ranked English candidates.
+    Word(RankedWord),
+}
+
+/// The result of [`Untranslation::rank`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedUntranslation {
+    segments: Vec<RankedSegment>,
+}
+
+impl RankedUntranslation {
+    /// The ranked words and non-word substrings, in order.
+    pub fn segments(&self) -> &[RankedSegment] {
+        &self.segments
+    }
+
+    /// Return the English text, using the most likely candidate for each word.
+    ///
+    /// Words without any candidate are kept in Pig-Latin.
+    pub fn to_english(&self) -> String {
+        let mut english = String::new();
+        for segment in &self.segments {
+            match segment {
+                RankedSegment::Verbatim(text) => english.push_str(text),
+                RankedSegment::Word(word) => {
+                    english.push_str(word.best().unwrap_or(&word.pig_latin))
+                }
+            }
+        }
+        english
+    }
+}
+
+/// # Translate Pig-Latin back into English.
+///
+/// This function undoes [`translate`](crate::translate) for
+/// [OTDoPL](crate#one-true-dialect) Pig-Latin.
+///
+/// The input is tokenized exactly like in [`translate`](crate::translate),
+/// so whitespace, layout, structure, and punctuation are preserved, as is the text
+/// that translating copies verbatim, such as URLs and numbers. Each word
+/// is then mapped onto all English words that translate into it. Where this
+/// is not unique, all candidates are returned.
+///
+/// ## Examples
+///
+/// Untranslate a sentence:
+/// ```rust
+/// # use pig_latin::untranslate;
+/// let english = untranslate("Ananabay izzapay!");
+/// assert_eq!(english.unambiguous(), Some(String::from("Banana pizza!")));
+/// ```
+///
+/// Ambiguous words keep all candidates:
+/// ```rust
+/// # use pig_latin::{untranslate, Segment};
+/// let english = untranslate("ashay");
+/// assert!(english.is_ambiguous());
+/// assert_eq!(
+///     english.segments(),
+///     [Segment::Word {
+///         pig_latin: String::from("ashay"),
+///         candidates: vec![String::from("as"), String::from("has"), String::from("sha")],
+///     }]
+/// );
+/// ```
+pub fn untranslate(pig_latin: &str) -> Untranslation {
+    untranslate_with(pig_latin, &OTDOPL)
+}
+
+/// # Translate Pig-Latin back into English, in any dialect.
+///
+/// Like [`untranslate`], but undoing the rules of `dialect` instead of OTDoPL.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use pig_latin::{untranslate_with, Dialect};
+/// let dialect = Dialect::builder().consonant_suffix("ei").build();
+/// let english = untranslate_with("Ananabei!", &dialect);
+/// assert_eq!(english.unambiguous(), Some(String::from("Banana!")));
+/// ```
+pub fn untranslate_with(pig_latin: &str, dialect: &Dialect) -> Untranslation {
+    let mut segments = Vec::new();
+    for (substring, piece) in split_pieces(pig_latin, dialect, &DEFAULT_PROTECTION) {
+        if piece == Piece::Word {
+            segments.push(Segment::Word {
+                pig_latin: substring.to_string(),
+                candidates: untranslate_word(substring, dialect),
+            });
+        } else if let Some(Segment::Verbatim(text)) = segments.last_mut() {
+            text.push_str(substring);
+        } else {
+            segments.push(Segment::Verbatim(substring.to_string()));
+        }
+    }
+    Untranslation { segments }
+}
+
+/// Find every English word that [`translate_word_with`] maps onto `pig_latin_word`.
+///
+/// Candidates are generated by undoing either translation rule at every
+/// possible cut, and kept only if translating them again reproduces the
+/// input (ignoring case). Vowel-rule candidates come first, followed by
+/// consonant-rule candidates ordered by the length of the moved consonants.
+/// A possessive "'s" is split off before, and re-attached after untranslating.
+fn untranslate_word(pig_latin_word: &str, dialect: &Dialect) -> Vec<String> {
+    if dialect.contractions() {
+        let (pig_latin_word, possessive) = split_possessive(pig_latin_word);
+        if !possessive.is_empty() {
+            return untranslate_word(pig_latin_word, dialect)
+                .into_iter()
+                .map(|candidate| candidate + possessive)
+                .collect();
+        }
+    }
+
+    let mut candidates: Vec<String> = Vec::new();
+    let mut consider = |candidate: String| {
+        // e.g. no leading apostrophes
+        let is_one_word = {
+            let mut words = split_words(&candidate, dialect);
+            matches!(
+                (words.next(), words.next()),
+                (Some((word, true)), None) if word == candidate
+            )
+        };
+        if is_one_word
+            && !candidates.contains(&candidate)
+            && translate_word_with(&candidate, dialect).to_lowercase()
+                == pig_latin_word.to_lowercase()
+        {
+            candidates.push(candidate);
+        }
+    };
+
+    if let Some(stem) = strip_suffix_ignore_ascii_case(pig_latin_word, dialect.vowel_suffix()) {
+        if !stem.is_empty() {
+            consider(stem.to_string());
+        }
+    }
+    if let Some(stem) = strip_suffix_ignore_ascii_case(pig_latin_word, dialect.consonant_suffix()) {
+        // the moved consonants are always whole grapheme clusters
+        let mut cuts: Vec<usize> = stem.grapheme_indices(true).map(|(idx, _)| idx).collect();
+        cuts.reverse();
+        for cut in cuts {
+            let moved = [&stem[cut..], &stem[..cut]].concat();
+            consider(apply_casing_like(&moved, pig_latin_word));
+        }
+    }
+    // words may be left untouched, see `VowellessPolicy::Untouched`
+    consider(pig_latin_word.to_string());
+    candidates
+}
+
+/// Like [`str::strip_suffix`], but ignoring ASCII case.
+fn strip_suffix_ignore_ascii_case<'a>(text: &'a str, suffix: &str) -> Option<&'a str> {
+    let cut = text.len().checked_sub(suffix.len())?;
+    if text.is_char_boundary(cut) && text[cut..].eq_ignore_ascii_case(suffix) {
+        Some(&text[..cut])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{translate, WordFrequencies};
+
+    #[test]
+    fn consonant_words() {
+        assert_eq!(untranslate_word("ananabay", &OTDOPL), ["banana"]);
+        assert_eq!(untranslate_word("izzapay", &OTDOPL), ["pizza"]);
+        // the cluster moved to the back may have been longer
+        assert_eq!(
+            untranslate_word("irstfay", &OTDOPL),
+            ["first", "tfirs", "stfir", "rstfi"]
+        );
+    }
+
+    #[test]
+    fn vowel_words() {
+        // any vowel-rule word could also have started with an "h"
+        assert_eq!(untranslate_word("arehay", &OTDOPL), ["are", "hare"]);
+        assert_eq!(
+            untranslate_word("omelethay", &OTDOPL),
+            ["omelet", "homelet", "thomele"]
+        );
+    }
+
+    #[test]
+    fn ambiguous_h() {
+        assert_eq!(untranslate_word("ashay", &OTDOPL), ["as", "has", "sha"]);
+        assert_eq!(untranslate_word("earhay", &OTDOPL), ["ear", "hear", "rhea"]);
+    }
+
+    #[test]
+    fn qu_and_casing() {
+        assert_eq!(untranslate_word("ERYQUAY", &OTDOPL), ["QUERY"]);
+        assert_eq!(untranslate_word("Estionquay", &OTDOPL), ["Question"]);
+        assert_eq!(untranslate_word("aintquay", &OTDOPL), ["quaint"]);
+    }
+
+    #[test]
+    fn grapheme_clusters() {
+        assert_eq!(
+            untranslate_word("eryqu\u{308}ay", &OTDOPL),
+            ["qu\u{308}ery"]
+        );
+        assert_eq!(untranslate_word("ac\u{327}ay", &OTDOPL), ["c\u{327}a"]);
+    }
+
+    #[test]
+    fn contractions() {
+        assert_eq!(untranslate_word("on'tday", &OTDOPL), ["don't", "n'tdo"]);
+        assert_eq!(untranslate_word("igpay's", &OTDOPL), ["pig's", "gpi's"]);
+        assert_eq!(
+            untranslate("Ethay igpay's ailtay isn'thay on'tday")
+                .rank(&WordFrequencies::default())
+                .to_english(),
+            "The pig's tail isn't don't"
+        );
+    }
+
+    #[test]
+    fn not_pig_latin() {
+        assert!(untranslate_word("hello", &OTDOPL).is_empty());
+        assert!(untranslate_word("ay", &OTDOPL).is_empty());
+        assert!(untranslate_word("ellohey", &OTDOPL).is_empty());
+    }
+
+    #[test]
+    fn round_trip_contains_source() {
+        let english = "This is all quite easy, is it not? Early-Adopters are ecstatic!";
+        let untranslated = untranslate(&translate(english));
+        let mut words = english.split(|c: char| c.is_ascii_punctuation() || c.is_whitespace());
+        for segment in untranslated.segments() {
+            if let Segment::Word { candidates, .. } = segment {
+                let word = words.find(|word| !word.is_empty()).unwrap();
+                assert!(candidates.iter().any(|candidate| candidate == word));
+            }
+        }
+    }
+
+    #[test]
+    fn verbatim_layout() {
+        let untranslated = untranslate("Isthay, ishay\n  igspay.");
+        let verbatim: Vec<_> = untranslated
+            .segments()
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Verbatim(text) => Some(text.as_str()),
+                Segment::Word { .. } => None,
+            })
+            .collect();
+        assert_eq!(verbatim, [", ", "\n  ", "."]);
+    }
+
+    #[test]
+    fn protected_round_trip() {
+        let english = "Get version 1.2.3 at https://example.com/pig now";
+        let untranslated = untranslate(&translate(english));
+        assert_eq!(
+            untranslated.segments()[1..4],
+            [
+                Segment::Verbatim(String::from(" ")),
+                Segment::Word {
+                    pig_latin: String::from("ersionvay"),
+                    candidates: vec![String::from("version"), String::from("nversio")],
+                },
+                Segment::Verbatim(String::from(" 1.2.3 ")),
+            ]
+        );
+        assert_eq!(
+            untranslated.rank(&WordFrequencies::default()).to_english(),
+            english
+        );
+    }
+
+    #[test]
+    fn dialect() {
+        let dialect = Dialect::builder()
+            .vowel_suffix("way")
+            .no_onset_units()
+            .build();
+        assert_eq!(untranslate_word("appleway", &dialect), ["apple", "wapple"]);
+        assert_eq!(untranslate_word("ueenqay", &dialect), ["queen", "nquee"]);
+        let dialect = Dialect::builder()
+            .vowelless(crate::VowellessPolicy::Untouched)
+            .build();
+        assert_eq!(untranslate_word("psst", &dialect), ["psst"]);
+        assert_eq!(untranslate_word("psstay", &dialect), Vec::<String>::new());
+        assert_eq!(untranslate_word("psst", &OTDOPL), Vec::<String>::new());
+    }
+
+    #[test]
+    fn rank_by_frequency() {
+        let model = WordFrequencies::from_reader("has 10\nsha 1\n".as_bytes()).unwrap();
+        let ranked = untranslate("ashay").rank(&model);
+        let RankedSegment::Word(word) = &ranked.segments()[0] else {
+            panic!("expected a word");
+        };
+        assert_eq!(word.best(), Some("has"));
+        assert_eq!(
+            word.alternatives,
+            [
+                (String::from("has"), 11.0 / 14.0),
+                (String::from("sha"), 2.0 / 14.0),
+                (String::from("as"), 1.0 / 14.0),
+            ]
+        );
+        assert_eq!(word.confidence(), 11.0 / 14.0);
+    }
+
+    #[test]
+    fn rank_default_model() {
+        let english = "This is all quite easy, is it not? Early-Adopters are ecstatic!";
+        let ranked = untranslate(&translate(english)).rank(&WordFrequencies::default());
+        assert_eq!(
+            ranked.to_english(),
+            "This is all quite easy, is it not? Early-Adopters are ecstatic!"
+        );
+    }
+
+    #[test]
+    fn rank_without_candidates() {
+        let ranked = untranslate("hello ay").rank(&WordFrequencies::default());
+        assert_eq!(ranked.to_english(), "hello ay");
+        let RankedSegment::Word(word) = &ranked.segments()[0] else {
+            panic!("expected a word");
+        };
+        assert_eq!(word.best(), None);
+        assert_eq!(word.confidence(), 0.0);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(untranslate("").segments(), []);
+        assert_eq!(untranslate("").unambiguous(), Some(String::new()));
+    }
+}
@@ -0,0 +1,136 @@
+//! Models of how likely English words are, used to rank the candidates of
+//! [`untranslate`](crate::untranslate).
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// A model of how common English words are.
+///
+/// Implement this to plug a custom language model into
+/// [`Untranslation::rank`](crate::Untranslation::rank).
+pub trait WordModel {
+    /// Return a non-negative, relative frequency for `word`.
+    ///
+    /// `word` is always passed in lower case. Unknown words should get `0.0`.
+    fn frequency(&self, word: &str) -> f64;
+}
+
+/// A [`WordModel`] based on a list of words with counts.
+///
+/// The word list is a plain text file with one word per line, optionally
+/// followed by whitespace and a count. Words without a count are counted once,
+/// and empty lines or lines starting with `#` are ignored:
+/// ```text
+/// # word count
+/// the 100000
+/// of 50000
+/// pig
+/// ```
+///
+/// [`WordFrequencies::default`] provides a small, embedded list of common English words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordFrequencies {
+    counts: HashMap<String, u64>,
+}
+
+impl WordFrequencies {
+    /// Read a word list from `reader`. The counts of a word listed more than once
+    /// are added up, saturating at [`u64::MAX`].
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if a count is not
+    /// a non-negative integer.
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let mut counts = HashMap::new();
+        for (line_idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let word = fields.next().unwrap_or_default().to_lowercase();
+            let count = match fields.next() {
+                None => 1,
+                Some(count) => count.parse::<u64>().map_err(|error| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid count on line {}: {error}", line_idx + 1),
+                    )
+                })?,
+            };
+            let total = counts.entry(word).or_insert(0u64);
+            *total = total.saturating_add(count);
+        }
+        Ok(WordFrequencies { counts })
+    }
+
+    /// Read a word list from the file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// The number of distinct words in the list.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Return `true` if the list contains no words.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+impl Default for WordFrequencies {
+    /// A small list of common English words, embedded into the library.
+    fn default() -> Self {
+        Self::from_reader(include_str!("english_words.txt").as_bytes())
+            .expect("the embedded word list should be valid")
+    }
+}
+
+impl WordModel for WordFrequencies {
+    fn frequency(&self, word: &str) -> f64 {
+        self.counts.get(word).copied().unwrap_or(0) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_word_list() {
+        let words = WordFrequencies::from_reader(
+            "# comment\nthe 10\n\n  Pig\npig 2\nlatin\t3\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(words.len(), 3);
+        assert_eq!(words.frequency("the"), 10.0);
+        assert_eq!(words.frequency("pig"), 3.0);
+        assert_eq!(words.frequency("latin"), 3.0);
+        assert_eq!(words.frequency("unknown"), 0.0);
+    }
+
+    #[test]
+    fn invalid_count() {
+        let error = WordFrequencies::from_reader("the ten\n".as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn saturating_counts() {
+        let words =
+            WordFrequencies::from_reader(format!("pig {}\npig 2\n", u64::MAX).as_bytes()).unwrap();
+        assert_eq!(words.frequency("pig"), u64::MAX as f64);
+    }
+
+    #[test]
+    fn embedded_default() {
+        let words = WordFrequencies::default();
+        assert!(!words.is_empty());
+        assert!(words.frequency("the") > words.frequency("pig"));
+        assert!(words.frequency("pig") > 0.0);
+    }
+}